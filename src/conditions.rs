@@ -1,6 +1,8 @@
 use crate::ElementPredicate;
+use std::sync::Arc;
 use stringmatch::Needle;
-use thirtyfour::error::WebDriverResult;
+use thirtyfour::error::{WebDriverError, WebDriverErrorInfo, WebDriverResult};
+use thirtyfour::{By, ScriptArgs, WebDriverCommands, WebElement};
 
 pub(crate) fn handle_errors(
     result: WebDriverResult<bool>,
@@ -22,6 +24,50 @@ pub(crate) fn negate(result: WebDriverResult<bool>, ignore_errors: bool) -> WebD
     handle_errors(result.map(|x| !x), ignore_errors)
 }
 
+/// Predicate that returns true for elements that match all of the specified predicates.
+/// The predicates are evaluated in order, stopping at the first one that returns false.
+pub fn all_of(predicates: Vec<ElementPredicate>) -> ElementPredicate {
+    let predicates = Arc::new(predicates);
+    Box::new(move |elem| {
+        let predicates = predicates.clone();
+        Box::pin(async move {
+            for f in predicates.iter() {
+                if !f(elem).await? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        })
+    })
+}
+
+/// Predicate that returns true for elements that match any of the specified predicates.
+/// The predicates are evaluated in order, stopping at the first one that returns true.
+pub fn any_of(predicates: Vec<ElementPredicate>) -> ElementPredicate {
+    let predicates = Arc::new(predicates);
+    Box::new(move |elem| {
+        let predicates = predicates.clone();
+        Box::pin(async move {
+            for f in predicates.iter() {
+                if f(elem).await? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+    })
+}
+
+/// Predicate that returns true for elements that do not match the specified predicate.
+/// Any error is passed through unchanged, so error handling is determined by the
+/// inner predicate.
+pub fn not(predicate: ElementPredicate) -> ElementPredicate {
+    Box::new(move |elem| {
+        let fut = predicate(elem);
+        Box::pin(async move { fut.await.map(|x| !x) })
+    })
+}
+
 /// Predicate that returns true for elements that are enabled.
 pub fn element_is_enabled(ignore_errors: bool) -> ElementPredicate {
     Box::new(move |elem| {
@@ -65,11 +111,131 @@ pub fn element_is_clickable(ignore_errors: bool) -> ElementPredicate {
     })
 }
 
+/// Scroll the element into view if it is clickable, and return true if it is then displayed.
+async fn is_clickable_and_visible(elem: &WebElement<'_>) -> WebDriverResult<bool> {
+    if !elem.is_clickable().await? {
+        return Ok(false);
+    }
+    elem.scroll_into_view().await?;
+    elem.is_displayed().await
+}
+
+/// Predicate that returns true for elements that are clickable, and are still displayed
+/// after being scrolled into view.
+/// NOTE: This scrolls the element into view each time the element is clickable.
+pub fn element_is_clickable_and_visible(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move { handle_errors(is_clickable_and_visible(elem).await, ignore_errors) })
+    })
+}
+
+/// Return true if the element is the document's active (focused) element.
+async fn is_focused(elem: &WebElement<'_>) -> WebDriverResult<bool> {
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    let ret = elem
+        .session
+        .execute_script_with_args(
+            r#"
+            const active = document.activeElement;
+            return active ? active === arguments[0] : null;
+            "#,
+            &args,
+        )
+        .await?;
+    let focused: Option<bool> = ret.convert()?;
+    Ok(focused.unwrap_or(false))
+}
+
+/// Predicate that returns true for elements that currently have focus, i.e. that are
+/// the document's active element.
+/// NOTE: This requires JavaScript execution to be available.
+pub fn element_is_focused(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move { handle_errors(is_focused(elem).await, ignore_errors) })
+    })
+}
+
+/// Return true if the element is clickable and would itself receive a click at its
+/// center point, i.e. it is not covered by another element such as an overlay.
+async fn is_truly_clickable(elem: &WebElement<'_>) -> WebDriverResult<bool> {
+    if !elem.is_clickable().await? {
+        return Ok(false);
+    }
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    let ret = elem
+        .session
+        .execute_script_with_args(
+            r#"
+            const elem = arguments[0];
+            const rect = elem.getBoundingClientRect();
+            const hit = document.elementFromPoint(
+                rect.left + rect.width / 2,
+                rect.top + rect.height / 2
+            );
+            return hit !== null && (hit === elem || elem.contains(hit));
+            "#,
+            &args,
+        )
+        .await?;
+    ret.convert()
+}
+
+/// Predicate that returns true for elements that are clickable and are not covered by
+/// another element (such as a modal backdrop) at their center point.
+/// NOTE: This requires JavaScript execution to be available. Elements whose center is
+/// outside the viewport never match, so scroll the element into view first if needed.
+pub fn element_is_truly_clickable(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move { handle_errors(is_truly_clickable(elem).await, ignore_errors) })
+    })
+}
+
 /// Predicate that returns true for elements that are not clickable.
 pub fn element_is_not_clickable(ignore_errors: bool) -> ElementPredicate {
     Box::new(move |elem| Box::pin(async move { negate(elem.is_clickable().await, ignore_errors) }))
 }
 
+/// Predicate that returns true for elements that are present in the DOM.
+/// Note that this does not require the element to be displayed.
+pub fn element_is_present(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move { handle_errors(elem.is_present().await, ignore_errors) })
+    })
+}
+
+/// Predicate that returns true for elements that are no longer present in the DOM
+/// (i.e. the element reference is stale).
+pub fn element_is_not_present(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| Box::pin(async move { negate(elem.is_present().await, ignore_errors) }))
+}
+
+/// Return true if the bounding rectangle of the element intersects the current viewport.
+async fn is_in_viewport(elem: &WebElement<'_>) -> WebDriverResult<bool> {
+    let rect = elem.rect().await?;
+    let ret = elem
+        .session
+        .execute_script(
+            r#"return [window.pageXOffset, window.pageYOffset, window.innerWidth, window.innerHeight];"#,
+        )
+        .await?;
+    let (x, y, width, height): (f32, f32, f32, f32) = ret.convert()?;
+    Ok(rect.x < x + width
+        && rect.y < y + height
+        && rect.x + rect.width > x
+        && rect.y + rect.height > y)
+}
+
+/// Predicate that returns true for elements that are at least partially within the
+/// current viewport.
+/// NOTE: This requires JavaScript execution to be available.
+pub fn element_is_in_viewport(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move { handle_errors(is_in_viewport(elem).await, ignore_errors) })
+    })
+}
+
 /// Predicate that returns true for elements that have the specified class name.
 /// See the `Needle` documentation for more details on text matching rules.
 /// In particular, it is recommended to use StringMatch or Regex to perform a whole-word search.
@@ -89,6 +255,27 @@ where
     })
 }
 
+/// Predicate that returns true for elements where any one of their (whitespace-separated)
+/// class names matches the specified class name.
+/// See the `Needle` documentation for more details on text matching rules.
+/// Unlike `element_has_class()`, each class name is matched separately, so a full match
+/// can be used to check for a single class.
+pub fn element_has_class_token<N>(class_name: N, ignore_errors: bool) -> ElementPredicate
+where
+    N: Needle + Clone + Send + Sync + 'static,
+{
+    Box::new(move |elem| {
+        let class_name = class_name.clone();
+        Box::pin(async move {
+            match elem.class_name().await {
+                Ok(Some(x)) => Ok(x.split_whitespace().any(|c| class_name.is_match(c))),
+                Ok(None) => Ok(false),
+                Err(e) => handle_errors(Err(e), ignore_errors),
+            }
+        })
+    })
+}
+
 /// Predicate that returns true for elements that do not contain the specified class name.
 /// See the `Needle` documentation for more details on text matching rules.
 /// In particular, it is recommended to use StringMatch or Regex to perform a whole-word search.
@@ -134,6 +321,150 @@ where
     })
 }
 
+/// Predicate that returns true for elements whose text is non-empty after trimming whitespace.
+pub fn element_text_not_empty(ignore_errors: bool) -> ElementPredicate {
+    Box::new(move |elem| {
+        Box::pin(async move {
+            handle_errors(elem.text().await.map(|x| !x.trim().is_empty()), ignore_errors)
+        })
+    })
+}
+
+/// Return true if the element has a sibling that matches the specified W3C selector.
+/// If `preceding` is true, only siblings before the element are checked, otherwise only
+/// siblings after the element are checked.
+async fn has_sibling(
+    elem: &WebElement<'_>,
+    selector_name: &str,
+    selector_query: &str,
+    preceding: bool,
+) -> WebDriverResult<bool> {
+    let script = match selector_name {
+        "css selector" => {
+            r#"
+            const next = (e) => arguments[2] ? e.previousElementSibling : e.nextElementSibling;
+            for (let e = next(arguments[0]); e; e = next(e)) {
+                if (e.matches(arguments[1])) return true;
+            }
+            return false;
+            "#
+        }
+        "xpath" => {
+            r#"
+            const axis = arguments[2] ? "preceding-sibling::" : "following-sibling::";
+            const result = document.evaluate(axis + arguments[1], arguments[0], null,
+                XPathResult.FIRST_ORDERED_NODE_TYPE, null);
+            return result.singleNodeValue !== null;
+            "#
+        }
+        _ => {
+            return Err(WebDriverError::InvalidSelector(WebDriverErrorInfo::new(&format!(
+                "Selector type {} is not supported for sibling filters. \
+                 Use a CSS or XPath selector instead",
+                selector_name
+            ))));
+        }
+    };
+
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    args.push(selector_query)?;
+    args.push(preceding)?;
+    let ret = elem.session.execute_script_with_args(script, &args).await?;
+    ret.convert()
+}
+
+/// Return a predicate that checks for a sibling matching the specified selector.
+fn element_has_sibling(by: By<'_>, preceding: bool, ignore_errors: bool) -> ElementPredicate {
+    let selector = Arc::new(by.get_w3c_selector());
+    Box::new(move |elem| {
+        let selector = selector.clone();
+        Box::pin(async move {
+            handle_errors(
+                has_sibling(elem, &selector.name, &selector.query, preceding).await,
+                ignore_errors,
+            )
+        })
+    })
+}
+
+/// Predicate that returns true for elements that have a preceding sibling (i.e. an
+/// element with the same parent that comes before it) matching the specified selector.
+///
+/// CSS-based selectors (Id, Name, Tag, ClassName and Css) support the full CSS selector
+/// syntax. An XPath selector is evaluated as a step on the `preceding-sibling` axis, so it
+/// should be a node test with optional predicates, e.g. `By::XPath("label[.='Password']")`.
+/// Link text selectors are not supported.
+/// NOTE: This requires JavaScript execution to be available.
+pub fn element_has_preceding_sibling(by: By<'_>, ignore_errors: bool) -> ElementPredicate {
+    element_has_sibling(by, true, ignore_errors)
+}
+
+/// Predicate that returns true for elements that have a following sibling (i.e. an
+/// element with the same parent that comes after it) matching the specified selector.
+/// See `element_has_preceding_sibling()` for the supported selectors.
+/// NOTE: This requires JavaScript execution to be available.
+pub fn element_has_following_sibling(by: By<'_>, ignore_errors: bool) -> ElementPredicate {
+    element_has_sibling(by, false, ignore_errors)
+}
+
+/// Return the `textContent` of the element, which includes the text of hidden descendants.
+async fn text_content(elem: &WebElement<'_>) -> WebDriverResult<String> {
+    let mut args = ScriptArgs::new();
+    args.push(elem)?;
+    let ret =
+        elem.session.execute_script_with_args("return arguments[0].textContent;", &args).await?;
+    let text: Option<String> = ret.convert()?;
+    Ok(text.unwrap_or_default())
+}
+
+/// Normalize whitespace in the specified text, by trimming leading and trailing whitespace
+/// and collapsing each run of whitespace (including newlines, tabs and non-breaking spaces)
+/// into a single space.
+///
+/// ```rust
+/// use thirtyfour_query::conditions::normalize_whitespace;
+/// assert_eq!(normalize_whitespace("  Hello \n\t  world "), "Hello world");
+/// ```
+pub fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Predicate that returns true for elements whose text, with whitespace normalized, matches
+/// the specified text. See `normalize_whitespace()` for how whitespace is normalized.
+/// See the `Needle` documentation for more details on text matching rules.
+pub fn element_has_normalized_text<N>(text: N, ignore_errors: bool) -> ElementPredicate
+where
+    N: Needle + Clone + Send + Sync + 'static,
+{
+    Box::new(move |elem| {
+        let text = text.clone();
+        Box::pin(async move {
+            handle_errors(
+                elem.text().await.map(|x| text.is_match(&normalize_whitespace(&x))),
+                ignore_errors,
+            )
+        })
+    })
+}
+
+/// Predicate that returns true for elements whose `textContent` contains the specified text.
+/// Unlike `element_has_text()`, this includes text that is not rendered, such as the
+/// text of hidden or collapsed elements.
+/// See the `Needle` documentation for more details on text matching rules.
+/// NOTE: This requires JavaScript execution to be available.
+pub fn element_has_text_content<N>(text: N, ignore_errors: bool) -> ElementPredicate
+where
+    N: Needle + Clone + Send + Sync + 'static,
+{
+    Box::new(move |elem| {
+        let text = text.clone();
+        Box::pin(async move {
+            handle_errors(text_content(elem).await.map(|x| text.is_match(&x)), ignore_errors)
+        })
+    })
+}
+
 /// Predicate that returns true for elements that have the specified value.
 /// See the `Needle` documentation for more details on text matching rules.
 pub fn element_has_value<N>(value: N, ignore_errors: bool) -> ElementPredicate
@@ -220,6 +551,133 @@ where
     })
 }
 
+/// Predicate that returns true for elements that have the specified `name` attribute.
+/// See the `Needle` documentation for more details on text matching rules.
+///
+/// NOTE: This is the HTML `name` attribute (as used by form fields), not the accessible
+/// name of the element (e.g. from its label or `aria-label`).
+pub fn element_has_name<N>(name: N, ignore_errors: bool) -> ElementPredicate
+where
+    N: Needle + Clone + Send + Sync + 'static,
+{
+    element_has_attribute("name", name, ignore_errors)
+}
+
+/// Predicate that returns true for elements that have the specified attribute, regardless
+/// of its value. This is useful for boolean attributes such as `disabled` or `checked`.
+pub fn element_has_attribute_present<S>(attribute_name: S, ignore_errors: bool) -> ElementPredicate
+where
+    S: Into<String>,
+{
+    let attribute_name: String = attribute_name.into();
+    Box::new(move |elem| {
+        let attribute_name: String = attribute_name.clone();
+        Box::pin(async move {
+            handle_errors(
+                elem.get_attribute(&attribute_name).await.map(|x| x.is_some()),
+                ignore_errors,
+            )
+        })
+    })
+}
+
+/// Predicate that returns true for elements that do not have the specified attribute.
+pub fn element_has_attribute_absent<S>(attribute_name: S, ignore_errors: bool) -> ElementPredicate
+where
+    S: Into<String>,
+{
+    let attribute_name: String = attribute_name.into();
+    Box::new(move |elem| {
+        let attribute_name: String = attribute_name.clone();
+        Box::pin(async move {
+            negate(elem.get_attribute(&attribute_name).await.map(|x| x.is_some()), ignore_errors)
+        })
+    })
+}
+
+/// Predicate that returns true for elements that have the specified attribute with a value
+/// exactly equal to any one of the specified values.
+/// NOTE: If the attribute is missing, the predicate returns false.
+pub fn element_has_attribute_one_of<S>(
+    attribute_name: S,
+    values: Vec<String>,
+    ignore_errors: bool,
+) -> ElementPredicate
+where
+    S: Into<String>,
+{
+    let attribute_name: String = attribute_name.into();
+    let values = Arc::new(values);
+    Box::new(move |elem| {
+        let attribute_name: String = attribute_name.clone();
+        let values = values.clone();
+        Box::pin(async move {
+            match elem.get_attribute(&attribute_name).await {
+                Ok(Some(x)) => Ok(values.contains(&x)),
+                Ok(None) => Ok(false),
+                Err(e) => handle_errors(Err(e), ignore_errors),
+            }
+        })
+    })
+}
+
+/// Predicate that returns true for elements that have the specified attribute with a numeric
+/// value that satisfies the specified function.
+///
+/// NOTE: If the attribute is missing or cannot be parsed as an `f64`, the predicate
+/// returns false.
+pub fn element_has_attribute_number<S, F>(
+    attribute_name: S,
+    pred: F,
+    ignore_errors: bool,
+) -> ElementPredicate
+where
+    S: Into<String>,
+    F: Fn(f64) -> bool + Send + Sync + 'static,
+{
+    let attribute_name: String = attribute_name.into();
+    let pred = Arc::new(pred);
+    Box::new(move |elem| {
+        let attribute_name: String = attribute_name.clone();
+        let pred = pred.clone();
+        Box::pin(async move {
+            match elem.get_attribute(&attribute_name).await {
+                Ok(Some(x)) => Ok(x.trim().parse::<f64>().map(|n| pred(n)).unwrap_or(false)),
+                Ok(None) => Ok(false),
+                Err(e) => handle_errors(Err(e), ignore_errors),
+            }
+        })
+    })
+}
+
+/// Predicate that returns true once the specified attribute no longer matches the specified
+/// (old) value. See the `Needle` documentation for more details on text matching rules.
+///
+/// NOTE: If the attribute is missing, this is treated as a change, and the predicate
+/// returns true.
+pub fn element_attribute_changes_from<S, N>(
+    attribute_name: S,
+    old_value: N,
+    ignore_errors: bool,
+) -> ElementPredicate
+where
+    S: Into<String>,
+    N: Needle + Clone + Send + Sync + 'static,
+{
+    let attribute_name: String = attribute_name.into();
+    Box::new(move |elem| {
+        let attribute_name: String = attribute_name.clone();
+        let old_value = old_value.clone();
+        Box::pin(async move {
+            match elem.get_attribute(&attribute_name).await {
+                Ok(Some(x)) => Ok(!old_value.is_match(&x)),
+                Ok(None) => Ok(true),
+                Err(e) => handle_errors(Err(e), ignore_errors),
+            }
+        })
+    })
+}
+
 /// Predicate that returns true for elements that have all of the specified attributes with the
 /// specified values. See the `Needle` documentation for more details on text matching rules.
 pub fn element_has_attributes<S, N>(
@@ -103,6 +103,10 @@
 //! All timeout, interval and ElementPoller details can be overridden on a per-call basis if
 //! desired. See the `ElementQuery` documentation for more details.
 //!
+//! All poller timeouts can also be scaled globally by setting the `THIRTYFOUR_TIMEOUT_SCALE`
+//! environment variable, e.g. `THIRTYFOUR_TIMEOUT_SCALE=2.5` on a slow CI machine.
+//! See `ElementPoller` for more details.
+//!
 //! ### ElementWaiter
 //!
 //! First, import the following:
@@ -1,8 +1,69 @@
+use log::warn;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use thirtyfour::error::{WebDriverError, WebDriverErrorInfo};
 use thirtyfour::support::sleep;
 
+/// The environment variable used to scale the timeout of every ElementPoller.
+pub const TIMEOUT_SCALE_ENV_VAR: &str = "THIRTYFOUR_TIMEOUT_SCALE";
+
+/// The maximum value accepted for the timeout scale.
+const MAX_TIMEOUT_SCALE: f64 = 1000.0;
+
+/// The timeout scale, read once from the environment.
+static TIMEOUT_SCALE: Lazy<f64> =
+    Lazy::new(|| parse_timeout_scale(std::env::var(TIMEOUT_SCALE_ENV_VAR).ok().as_deref()));
+
+/// Parse the timeout scale. Missing or invalid values (including values that are not
+/// positive, or larger than `MAX_TIMEOUT_SCALE`) give a scale of 1.0.
+fn parse_timeout_scale(value: Option<&str>) -> f64 {
+    match value.and_then(|x| x.trim().parse::<f64>().ok()) {
+        Some(scale) if scale > 0.0 && scale <= MAX_TIMEOUT_SCALE => scale,
+        Some(_) => {
+            warn!("Ignoring invalid {} value: {:?}", TIMEOUT_SCALE_ENV_VAR, value.unwrap_or(""));
+            1.0
+        }
+        None => 1.0,
+    }
+}
+
+/// Scale the specified timeout by the timeout scale.
+fn scale_timeout(timeout: Duration) -> Duration {
+    scale_timeout_by(timeout, *TIMEOUT_SCALE)
+}
+
+/// Scale the specified timeout by the specified scale, saturating at `Duration::MAX`.
+fn scale_timeout_by(timeout: Duration, scale: f64) -> Duration {
+    if (scale - 1.0).abs() < f64::EPSILON {
+        timeout
+    } else {
+        Duration::try_from_secs_f64(timeout.as_secs_f64() * scale).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Return the Instant that is the specified timeout from now, scaled by
+/// `THIRTYFOUR_TIMEOUT_SCALE` in the same way as poller timeouts.
+///
+/// This is intended for sharing one overall deadline between several queries via
+/// `ElementQuery::with_deadline()`.
+///
+/// Returns None if the deadline is too far in the future to be represented (e.g. for
+/// `Duration::MAX`), meaning there is no deadline, in the same way as poller timeouts.
+pub fn deadline_after(timeout: Duration) -> Option<Instant> {
+    Instant::now().checked_add(scale_timeout(timeout))
+}
+
 /// Parameters used to determine the polling / timeout behaviour.
+///
+/// If the `THIRTYFOUR_TIMEOUT_SCALE` environment variable is set to a positive number
+/// (up to 1000), every timeout is multiplied by that number. This is useful for running
+/// the same tests on slower machines. The variable is read once, the first time a poller
+/// is used. It does not affect intervals, or the deadline of `UntilInstant`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ElementPoller {
     /// No polling, single attempt.
@@ -29,30 +90,303 @@ pub enum ElementPoller {
     /// specified timeout, then the polling attempts will continue until the
     /// timeout is reached instead.
     TimeoutWithIntervalAndMinTries(Duration, Duration, u32),
+    /// Wait for the specified duration, then make a single attempt.
+    /// This is useful when you know roughly how long something (e.g. an animation)
+    /// will take, and do not want to keep polling.
+    OnceAfter(Duration),
+    /// Poll once every interval, up to the specified deadline.
+    /// This is useful for sharing a single overall deadline between several queries,
+    /// regardless of when each query starts.
+    ///
+    /// NOTE: `Instant` cannot be serialized, so this poller cannot be stored in the
+    /// WebDriver config. It can only be set programmatically on a per-query basis,
+    /// e.g. via `with_poller()`.
+    #[serde(skip)]
+    UntilInstant(Instant, Duration),
+    /// Poll up to the specified timeout, with an interval that adapts to the observed
+    /// latency of the WebDriver calls. The interval is a quarter of the average duration
+    /// of recent poll attempts, bounded by the specified minimum and maximum intervals.
+    /// This avoids polling too often on a slow remote grid, and too rarely on a fast
+    /// local browser.
+    ///
+    /// The parameters are (timeout, min interval, max interval).
+    Adaptive(Duration, Duration, Duration),
+}
+
+/// The number of recent latencies used to compute the interval for
+/// `ElementPoller::Adaptive`.
+const ADAPTIVE_LATENCY_SAMPLES: usize = 5;
+
+/// The fraction of the average latency used as the interval for `ElementPoller::Adaptive`.
+const ADAPTIVE_LATENCY_FRACTION: f64 = 0.25;
+
+/// Function signature for computing the delay before each poll attempt.
+/// The function is passed the number of the upcoming attempt (starting from 2, since the
+/// first attempt is never delayed).
+pub type IntervalFn = Arc<dyn Fn(u32) -> Duration + Send + Sync>;
+
+impl fmt::Display for ElementPoller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementPoller::NoWait => write!(f, "NoWait"),
+            ElementPoller::TimeoutWithInterval(timeout, interval) => {
+                write!(f, "TimeoutWithInterval({:?}, {:?})", timeout, interval)
+            }
+            ElementPoller::NumTriesWithInterval(num_tries, interval) => {
+                write!(f, "NumTriesWithInterval({}, {:?})", num_tries, interval)
+            }
+            ElementPoller::TimeoutWithIntervalAndMinTries(timeout, interval, num_tries) => write!(
+                f,
+                "TimeoutWithIntervalAndMinTries({:?}, {:?}, {})",
+                timeout, interval, num_tries
+            ),
+            ElementPoller::OnceAfter(delay) => write!(f, "OnceAfter({:?})", delay),
+            ElementPoller::UntilInstant(deadline, interval) => write!(
+                f,
+                "UntilInstant({:?} remaining, {:?})",
+                deadline.saturating_duration_since(Instant::now()),
+                interval
+            ),
+            ElementPoller::Adaptive(timeout, min_interval, max_interval) => {
+                write!(f, "Adaptive({:?}, {:?}, {:?})", timeout, min_interval, max_interval)
+            }
+        }
+    }
+}
+
+impl ElementPoller {
+    /// Check that the parameters of this ElementPoller make sense, and return a
+    /// description of the problem if they do not. For example, an interval longer than
+    /// the timeout means only a single attempt will ever be made.
+    ///
+    /// This is checked automatically before polling, and an invalid poller results in
+    /// a WebDriverError::InvalidArgument error.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use thirtyfour_query::ElementPoller;
+    /// # use std::time::Duration;
+    /// let poller = ElementPoller::TimeoutWithInterval(Duration::ZERO, Duration::from_secs(5));
+    /// assert!(poller.validate().is_err());
+    /// let poller = ElementPoller::TimeoutWithInterval(Duration::from_secs(5), Duration::from_millis(500));
+    /// assert!(poller.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            ElementPoller::TimeoutWithInterval(timeout, interval) if interval > timeout => Err(
+                format!("the interval ({:?}) is longer than the timeout ({:?})", interval, timeout),
+            ),
+            ElementPoller::NumTriesWithInterval(0, _) => {
+                Err(String::from("the number of tries must be at least 1"))
+            }
+            ElementPoller::TimeoutWithIntervalAndMinTries(timeout, _, num_tries)
+                if timeout == Duration::ZERO && num_tries > 0 =>
+            {
+                Err(String::from(
+                    "the timeout is zero but the minimum number of tries is not. \
+                     Use NumTriesWithInterval instead",
+                ))
+            }
+            ElementPoller::TimeoutWithIntervalAndMinTries(timeout, interval, _)
+                if interval > timeout =>
+            {
+                Err(format!(
+                    "the interval ({:?}) is longer than the timeout ({:?})",
+                    interval, timeout
+                ))
+            }
+            ElementPoller::Adaptive(_, min_interval, max_interval)
+                if min_interval > max_interval =>
+            {
+                Err(format!(
+                    "the minimum interval ({:?}) is longer than the maximum interval ({:?})",
+                    min_interval, max_interval
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate this ElementPoller, and return a WebDriverError describing the problem
+    /// if it is invalid.
+    pub(crate) fn validation_error(&self) -> Option<WebDriverError> {
+        self.validate().err().map(|e| {
+            WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "Invalid ElementPoller {:?}: {}",
+                self, e
+            )))
+        })
+    }
+}
+
+/// Parse a duration such as `500ms`, `10s` or `2m`.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| {
+        format!("invalid duration {:?}: expected a whole number followed by ms, s or m", value)
+    })?;
+    match unit {
+        "ms" => Ok(Duration::from_millis(number)),
+        "s" => Ok(Duration::from_secs(number)),
+        "m" => Ok(Duration::from_secs(number.saturating_mul(60))),
+        _ => Err(format!("invalid duration {:?}: the unit must be ms, s or m", value)),
+    }
+}
+
+/// Parse an ElementPoller from a string, e.g. from a config file or command-line flag.
+///
+/// The string is either `nowait`, or a comma-separated list of `key:value` pairs.
+/// Durations are a whole number followed by `ms`, `s` or `m`. The keys used determine
+/// the variant:
+///
+/// | String                                          | ElementPoller                    |
+/// |-------------------------------------------------|----------------------------------|
+/// | `nowait`                                        | `NoWait`                         |
+/// | `timeout:10s,interval:500ms`                    | `TimeoutWithInterval`            |
+/// | `tries:5,interval:500ms`                        | `NumTriesWithInterval`           |
+/// | `timeout:10s,interval:500ms,tries:3`            | `TimeoutWithIntervalAndMinTries` |
+/// | `after:2s`                                      | `OnceAfter`                      |
+/// | `timeout:10s,min_interval:50ms,max_interval:1s` | `Adaptive`                       |
+///
+/// Keys may be given in any order. The parsed poller is also checked with `validate()`.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_query::ElementPoller;
+/// # use std::time::Duration;
+/// let poller: ElementPoller = "timeout:10s, interval:500ms".parse().unwrap();
+/// assert!(matches!(poller, ElementPoller::TimeoutWithInterval(t, i)
+///     if t == Duration::from_secs(10) && i == Duration::from_millis(500)));
+///
+/// let poller: ElementPoller = "tries:5,interval:1s".parse().unwrap();
+/// assert!(matches!(poller, ElementPoller::NumTriesWithInterval(5, _)));
+///
+/// let poller: ElementPoller = "interval:100ms,tries:3,timeout:2m".parse().unwrap();
+/// assert!(matches!(poller, ElementPoller::TimeoutWithIntervalAndMinTries(t, _, 3)
+///     if t == Duration::from_secs(120)));
+///
+/// let poller: ElementPoller = "after:2s".parse().unwrap();
+/// assert!(matches!(poller, ElementPoller::OnceAfter(d) if d == Duration::from_secs(2)));
+///
+/// let poller: ElementPoller = "timeout:10s,min_interval:50ms,max_interval:1s".parse().unwrap();
+/// assert!(matches!(poller, ElementPoller::Adaptive(..)));
+///
+/// assert!(matches!("nowait".parse(), Ok(ElementPoller::NoWait)));
+///
+/// // Malformed input.
+/// assert!("".parse::<ElementPoller>().is_err());
+/// assert!("timeout:10".parse::<ElementPoller>().is_err());
+/// assert!("timeout:10s".parse::<ElementPoller>().is_err());
+/// assert!("timeout:10s,interval:1h".parse::<ElementPoller>().is_err());
+/// assert!("timeout:10s,timeout:5s,interval:1s".parse::<ElementPoller>().is_err());
+/// assert!("timeout:10s,speed:fast".parse::<ElementPoller>().is_err());
+/// assert!("tries:many,interval:1s".parse::<ElementPoller>().is_err());
+/// assert!("after:1s,interval:1s".parse::<ElementPoller>().is_err());
+/// // Parsed, but fails validation because the interval is longer than the timeout.
+/// assert!("timeout:1s,interval:5s".parse::<ElementPoller>().is_err());
+/// ```
+impl FromStr for ElementPoller {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("nowait") {
+            return Ok(ElementPoller::NoWait);
+        }
+
+        let mut timeout = None;
+        let mut interval = None;
+        let mut tries = None;
+        let mut after = None;
+        let mut min_interval = None;
+        let mut max_interval = None;
+        for part in s.split(',') {
+            let (key, value) = part
+                .split_once(':')
+                .ok_or_else(|| format!("expected key:value, found {:?}", part.trim()))?;
+            let key = key.trim();
+            let duplicate = match key {
+                "timeout" => timeout.replace(parse_duration(value)?).is_some(),
+                "interval" => interval.replace(parse_duration(value)?).is_some(),
+                "after" => after.replace(parse_duration(value)?).is_some(),
+                "min_interval" => min_interval.replace(parse_duration(value)?).is_some(),
+                "max_interval" => max_interval.replace(parse_duration(value)?).is_some(),
+                "tries" => {
+                    let value: u32 = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("invalid number of tries {:?}", value.trim()))?;
+                    tries.replace(value).is_some()
+                }
+                _ => return Err(format!("unknown key {:?}", key)),
+            };
+            if duplicate {
+                return Err(format!("duplicate key {:?}", key));
+            }
+        }
+
+        let poller = match (timeout, interval, tries, after, min_interval, max_interval) {
+            (Some(t), Some(i), None, None, None, None) => ElementPoller::TimeoutWithInterval(t, i),
+            (None, Some(i), Some(n), None, None, None) => ElementPoller::NumTriesWithInterval(n, i),
+            (Some(t), Some(i), Some(n), None, None, None) => {
+                ElementPoller::TimeoutWithIntervalAndMinTries(t, i, n)
+            }
+            (None, None, None, Some(d), None, None) => ElementPoller::OnceAfter(d),
+            (Some(t), None, None, None, Some(min), Some(max)) => {
+                ElementPoller::Adaptive(t, min, max)
+            }
+            _ => {
+                return Err(format!(
+                    "unsupported combination of keys in {:?}. See the documentation for \
+                     ElementPoller::from_str() for the supported formats",
+                    s
+                ))
+            }
+        };
+        poller.validate()?;
+        Ok(poller)
+    }
 }
 
 pub struct ElementPollerTicker {
-    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    unbounded: bool,
+    limit: Option<Instant>,
     interval: Option<Duration>,
+    interval_fn: Option<IntervalFn>,
+    adaptive: Option<(Duration, Duration)>,
+    latencies: VecDeque<Duration>,
+    delay: Option<Duration>,
     min_tries: u32,
     start: Instant,
+    next_tick: Instant,
     cur_tries: u32,
 }
 
 impl ElementPollerTicker {
     pub fn new(poller: ElementPoller) -> Self {
+        let start = Instant::now();
         let mut ticker = Self {
-            timeout: None,
+            deadline: None,
+            unbounded: false,
+            limit: None,
             interval: None,
+            interval_fn: None,
+            adaptive: None,
+            latencies: VecDeque::new(),
+            delay: None,
             min_tries: 0,
-            start: Instant::now(),
+            start,
+            next_tick: start,
             cur_tries: 0,
         };
 
         match poller {
             ElementPoller::NoWait => {}
             ElementPoller::TimeoutWithInterval(timeout, interval) => {
-                ticker.timeout = Some(timeout);
+                ticker.set_timeout(timeout);
                 ticker.interval = Some(interval);
             }
             ElementPoller::NumTriesWithInterval(num_tries, interval) => {
@@ -60,37 +394,264 @@ impl ElementPollerTicker {
                 ticker.min_tries = num_tries;
             }
             ElementPoller::TimeoutWithIntervalAndMinTries(timeout, interval, num_tries) => {
-                ticker.timeout = Some(timeout);
+                ticker.set_timeout(timeout);
                 ticker.interval = Some(interval);
                 ticker.min_tries = num_tries
             }
+            ElementPoller::OnceAfter(delay) => {
+                ticker.delay = Some(delay);
+                ticker.min_tries = 1;
+            }
+            ElementPoller::UntilInstant(deadline, interval) => {
+                ticker.deadline = Some(deadline);
+                ticker.interval = Some(interval);
+            }
+            ElementPoller::Adaptive(timeout, min_interval, max_interval) => {
+                ticker.set_timeout(timeout);
+                ticker.adaptive = Some((min_interval, max_interval));
+            }
         }
 
         ticker
     }
 
+    /// Set the deadline to the specified timeout (scaled by the timeout scale) after the
+    /// ticker was created. If the deadline is too far in the future to be represented
+    /// (e.g. for `Duration::MAX`), there is no deadline and polling continues indefinitely.
+    fn set_timeout(&mut self, timeout: Duration) {
+        match self.start.checked_add(scale_timeout(timeout)) {
+            Some(deadline) => self.deadline = Some(deadline),
+            None => self.unbounded = true,
+        }
+    }
+
+    /// Use the specified function to compute the delay before each poll attempt, instead of
+    /// the fixed interval from the ElementPoller. The delay is measured from the end of the
+    /// previous attempt, and is cut short if it would pass the timeout (or deadline).
+    /// The number of attempts and the timeout are still determined by the ElementPoller.
+    pub fn set_interval_fn(&mut self, f: IntervalFn) {
+        self.interval_fn = Some(f);
+    }
+
+    /// Stop polling once the specified deadline is reached, even if the ElementPoller
+    /// would otherwise continue (including any minimum number of tries).
+    /// If the ElementPoller has a later timeout, the timeout is brought forward.
+    pub fn limit_deadline(&mut self, deadline: Instant) {
+        self.limit = Some(self.limit.map_or(deadline, |x| x.min(deadline)));
+        if let Some(d) = self.deadline {
+            self.deadline = Some(d.min(deadline));
+        }
+    }
+
+    /// Record how long a poll attempt (or WebDriver call) took.
+    /// This is used to compute the interval for `ElementPoller::Adaptive`, and is
+    /// ignored by other pollers.
+    pub fn record_latency(&mut self, latency: Duration) {
+        if self.adaptive.is_some() {
+            if self.latencies.len() >= ADAPTIVE_LATENCY_SAMPLES {
+                self.latencies.pop_front();
+            }
+            self.latencies.push_back(latency);
+        }
+    }
+
+    /// Return the interval for `ElementPoller::Adaptive`, based on the recorded latencies.
+    fn adaptive_interval(&self, min_interval: Duration, max_interval: Duration) -> Duration {
+        if self.latencies.is_empty() {
+            return min_interval;
+        }
+        let total: Duration = self.latencies.iter().sum();
+        let average = total / self.latencies.len() as u32;
+        average.mul_f64(ADAPTIVE_LATENCY_FRACTION).max(min_interval).min(max_interval)
+    }
+
+    /// Wait for any initial delay required by the poller.
+    /// This should be called once, before the first poll attempt.
+    pub async fn wait_for_delay(&self) {
+        if let Some(delay) = self.delay {
+            // The delay is relative to when the ticker was created.
+            let elapsed = self.start.elapsed();
+            if elapsed < delay {
+                sleep(delay - elapsed).await;
+            }
+        }
+    }
+
+    /// Return true if this poller has a timeout (or deadline) and it has been reached.
+    pub fn timed_out(&self) -> bool {
+        let now = Instant::now();
+        self.deadline.map(|d| now >= d).unwrap_or(false)
+            || self.limit.map(|d| now >= d).unwrap_or(false)
+    }
+
+    /// Wait until the next poll attempt is due. Returns false (without waiting) if
+    /// polling should stop instead.
     pub async fn tick(&mut self) -> bool {
-        self.cur_tries += 1;
+        match self.advance() {
+            Some(due) => {
+                sleep_until(due).await;
+                true
+            }
+            None => false,
+        }
+    }
 
-        if self.timeout.filter(|t| &self.start.elapsed() < t).is_none()
+    /// Advance to the next poll attempt without waiting, and return the Instant at which
+    /// that attempt is due. Returns None if polling should stop instead.
+    ///
+    /// This allows several tickers to be advanced together, and then wait only once for
+    /// the latest of their due times.
+    pub fn advance(&mut self) -> Option<Instant> {
+        self.cur_tries = self.cur_tries.saturating_add(1);
+
+        let now = Instant::now();
+        if self.limit.map(|d| now >= d).unwrap_or(false) {
+            return None;
+        }
+
+        if !self.unbounded
+            && self.deadline.filter(|d| &now < d).is_none()
             && self.cur_tries >= self.min_tries
         {
-            return false;
+            return None;
         }
 
-        if let Some(i) = self.interval {
-            // Next poll is due no earlier than this long after the first poll started.
-            let minimum_elapsed = i * self.cur_tries;
-
-            // But this much time has elapsed since the first poll started.
-            let actual_elapsed = self.start.elapsed();
+        let delay = match (&self.interval_fn, self.adaptive) {
+            (Some(f), _) => Some(f(self.cur_tries.saturating_add(1))),
+            (None, Some((min_interval, max_interval))) => {
+                Some(self.adaptive_interval(min_interval, max_interval))
+            }
+            (None, None) => None,
+        };
 
-            if actual_elapsed < minimum_elapsed {
-                // So we need to wait this much longer.
-                sleep(minimum_elapsed - actual_elapsed).await;
+        if let Some(mut delay) = delay {
+            if let Some(deadline) = self.deadline.or(self.limit) {
+                delay = delay.min(deadline.saturating_duration_since(now));
             }
+            Some(now.checked_add(delay).unwrap_or(now))
+        } else if let Some(i) = self.interval {
+            // Next poll is due no earlier than one interval after the previous one was due.
+            // This is tracked incrementally (rather than multiplying the interval by the
+            // number of tries) so that it cannot overflow on long runs.
+            self.next_tick = self.next_tick.checked_add(i).unwrap_or(self.next_tick);
+            Some(self.limit.map_or(self.next_tick, |x| x.min(self.next_tick)))
+        } else {
+            Some(now)
+        }
+    }
+}
+
+/// Sleep until the specified Instant. Returns immediately if it has already passed.
+pub(crate) async fn sleep_until(due: Instant) {
+    let now = Instant::now();
+    if now < due {
+        sleep(due - now).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout_scale() {
+        assert_eq!(parse_timeout_scale(None), 1.0);
+        assert_eq!(parse_timeout_scale(Some("2.5")), 2.5);
+        assert_eq!(parse_timeout_scale(Some(" 3 ")), 3.0);
+        assert_eq!(parse_timeout_scale(Some("1000")), 1000.0);
+        assert_eq!(parse_timeout_scale(Some("0.1")), 0.1);
+    }
+
+    #[test]
+    fn test_parse_timeout_scale_invalid() {
+        assert_eq!(parse_timeout_scale(Some("")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("abc")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("0")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("-2")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("1000.1")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("NaN")), 1.0);
+        assert_eq!(parse_timeout_scale(Some("inf")), 1.0);
+    }
+
+    #[test]
+    fn test_scale_timeout_by() {
+        let timeout = Duration::from_secs(10);
+        assert_eq!(scale_timeout_by(timeout, 1.0), timeout);
+        assert_eq!(scale_timeout_by(timeout, 2.0), Duration::from_secs(20));
+        assert_eq!(scale_timeout_by(timeout, 0.5), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_scale_timeout_by_saturates() {
+        assert_eq!(scale_timeout_by(Duration::MAX, 1000.0), Duration::MAX);
+        assert_eq!(scale_timeout_by(Duration::from_secs(u64::MAX / 2), 1000.0), Duration::MAX);
+    }
+
+    #[test]
+    fn test_deadline_after() {
+        let deadline = deadline_after(Duration::from_secs(10)).unwrap();
+        assert!(deadline > Instant::now());
+    }
+
+    #[test]
+    fn test_deadline_after_overflow() {
+        assert_eq!(deadline_after(Duration::MAX), None);
+    }
+
+    #[test]
+    fn test_ticker_num_tries() {
+        let num_tries = 1_000_000;
+        let interval = Duration::from_millis(1);
+        let mut ticker =
+            ElementPollerTicker::new(ElementPoller::NumTriesWithInterval(num_tries, interval));
+        let start = ticker.start;
+        let mut attempts = 1;
+        while let Some(due) = ticker.advance() {
+            // Each attempt is due exactly one interval after the previous one.
+            assert_eq!(due, start + interval * attempts);
+            attempts += 1;
+            assert!(attempts <= num_tries, "ticker did not stop");
         }
+        assert_eq!(attempts, num_tries);
+    }
+
+    #[test]
+    fn test_ticker_timeout() {
+        let timeout = Duration::from_millis(20);
+        let interval = Duration::from_millis(1);
+        let mut ticker =
+            ElementPollerTicker::new(ElementPoller::TimeoutWithInterval(timeout, interval));
+        let start = ticker.start;
+        let mut attempts = 1;
+        while let Some(due) = ticker.advance() {
+            assert_eq!(due, start + interval * attempts);
+            attempts += 1;
+        }
+        assert!(Instant::now() >= start + timeout);
+        assert!(ticker.timed_out());
+    }
 
-        true
+    #[test]
+    fn test_ticker_limit_deadline() {
+        let interval = Duration::from_secs(1);
+        let mut ticker =
+            ElementPollerTicker::new(ElementPoller::NumTriesWithInterval(u32::MAX, interval));
+        let limit = ticker.start + Duration::from_millis(3500);
+        ticker.limit_deadline(limit);
+        let dues: Vec<Instant> = (0..6).filter_map(|_| ticker.advance()).collect();
+        let start = ticker.start;
+        assert_eq!(
+            dues,
+            vec![start + interval, start + interval * 2, start + interval * 3, limit, limit, limit]
+        );
+    }
+
+    #[test]
+    fn test_ticker_interval_overflow() {
+        let mut ticker =
+            ElementPollerTicker::new(ElementPoller::NumTriesWithInterval(u32::MAX, Duration::MAX));
+        for _ in 0..1000 {
+            assert!(ticker.advance().is_some());
+        }
     }
 }
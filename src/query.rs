@@ -1,46 +1,135 @@
+use std::collections::HashSet;
 use std::mem;
-use std::sync::Arc;
-use std::time::Duration;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use futures::future::try_join_all;
+use futures::stream::{self, StreamExt, TryStreamExt};
 use futures::Future;
-use stringmatch::Needle;
+use log::warn;
+use serde_json::json;
+use stringmatch::{Needle, StringMatch};
 use thirtyfour::error::{WebDriverError, WebDriverErrorInfo};
 use thirtyfour::prelude::{WebDriver, WebDriverResult};
-use thirtyfour::{By, WebDriverCommands, WebDriverSession, WebElement};
+use thirtyfour::{
+    By, ElementId, ElementRef, ScriptArgs, WebDriverCommands, WebDriverSession, WebElement,
+};
 
 use crate::conditions::{handle_errors, negate};
-use crate::{conditions, ElementPoller, ElementPollerTicker, ElementPredicate};
+use crate::poller::sleep_until;
+use crate::{conditions, ElementPoller, ElementPollerTicker, ElementPredicate, IntervalFn};
 
 /// Get String containing comma-separated list of selectors used.
 fn get_selector_summary(selectors: &[ElementSelector]) -> String {
-    let criteria: Vec<String> = selectors.iter().map(|s| s.by.to_string()).collect();
+    let criteria: Vec<String> = selectors
+        .iter()
+        .map(|s| {
+            let mut criteria = s.by.to_string();
+            for by in &s.also {
+                criteria.push_str(&format!(" & {}", by));
+            }
+            criteria
+        })
+        .collect();
     format!("[{}]", criteria.join(","))
 }
 
-/// Helper function to return the NoSuchElement error struct.
-fn no_such_element(selectors: &[ElementSelector], description: &str) -> WebDriverError {
-    let element_description = if description.is_empty() {
+/// The maximum number of elements per selector to log when `debug_dump_on_error()` is used.
+const DEBUG_DUMP_MAX_ELEMENTS: usize = 5;
+
+/// Get String describing the element(s) being queried.
+fn get_element_description(description: &str) -> String {
+    if description.is_empty() {
         String::from("Element(s)")
     } else {
         format!("'{}' element(s)", description)
-    };
+    }
+}
 
+/// Helper function to return the NoSuchElement error struct.
+fn no_such_element(selectors: &[ElementSelector], description: &str) -> WebDriverError {
     WebDriverError::NoSuchElement(WebDriverErrorInfo::new(&format!(
         "{} not found using selectors: {}",
-        element_description,
+        get_element_description(description),
         &get_selector_summary(selectors)
     )))
 }
 
+/// Helper function to return the error for a query whose elements did not satisfy the
+/// expected condition, where `problem` describes what went wrong (e.g. "not found").
+/// If the poller timed out (or the maximum number of WebDriver calls was reached), a
+/// Timeout error is returned, otherwise NoSuchElement.
+fn poll_error(
+    selectors: &[ElementSelector],
+    description: &str,
+    poller: &ElementPoller,
+    stats: &QueryStats,
+    problem: &str,
+) -> WebDriverError {
+    if stats.max_calls_reached {
+        WebDriverError::Timeout(format!(
+            "{} {} using selectors: {} (stopped after reaching the maximum of {} \
+             WebDriver call(s) in {} attempt(s), poller: {})",
+            get_element_description(description),
+            problem,
+            &get_selector_summary(selectors),
+            stats.calls,
+            stats.attempts,
+            poller
+        ))
+    } else if stats.timed_out {
+        WebDriverError::Timeout(format!(
+            "{} {} using selectors: {} (timed out after {} attempt(s) in {:?}, poller: {})",
+            get_element_description(description),
+            problem,
+            &get_selector_summary(selectors),
+            stats.attempts,
+            stats.elapsed,
+            poller
+        ))
+    } else {
+        WebDriverError::NoSuchElement(WebDriverErrorInfo::new(&format!(
+            "{} {} using selectors: {} (poller: {})",
+            get_element_description(description),
+            problem,
+            &get_selector_summary(selectors),
+            poller
+        )))
+    }
+}
+
+/// Find all elements matching the specified selector from the specified source.
+async fn find_all_from_source<'a>(
+    source: &ElementQuerySource<'a>,
+    by: By<'a>,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    match source {
+        ElementQuerySource::Driver(driver) => driver.find_elements(by).await,
+        ElementQuerySource::Element(element) => element.find_elements(by).await,
+        ElementQuerySource::ShadowRoot(element) => find_elements_in_shadow_root(element, by).await,
+        ElementQuerySource::Ancestors(element) => find_ancestors(element, by).await,
+    }
+}
+
 /// An ElementSelector contains a selector method (By) as well as zero or more filters.
 /// The filters will be applied to any elements matched by the selector.
 /// Selectors and filters all run in full on every poll iteration.
+///
+/// Filters are stored behind an `Arc`, so cloning a selector is cheap.
+#[derive(Clone)]
 pub struct ElementSelector<'a> {
     /// If false (default), find_elements() will be used. If true, find_element() will be used
     /// instead. See notes below for `with_single_selector()` for potential pitfalls.
     pub single: bool,
     pub by: By<'a>,
-    pub filters: Vec<ElementPredicate>,
+    /// Additional selectors that elements must also match. Only elements matched by `by`
+    /// and every one of these selectors are returned.
+    pub also: Vec<By<'a>>,
+    pub filters: Vec<Arc<ElementPredicate>>,
+    /// If set, this ElementPoller will be used for this selector instead of the
+    /// ElementPoller for the ElementQuery.
+    pub poller: Option<ElementPoller>,
 }
 
 impl<'a> ElementSelector<'a> {
@@ -52,7 +141,9 @@ impl<'a> ElementSelector<'a> {
         Self {
             single: false,
             by: by.clone(),
+            also: Vec::new(),
             filters: Vec::new(),
+            poller: None,
         }
     }
 
@@ -68,9 +159,89 @@ impl<'a> ElementSelector<'a> {
         self.single = true;
     }
 
+    /// Use the specified ElementPoller for this selector, rather than the ElementPoller
+    /// for the ElementQuery.
+    pub fn set_poller(&mut self, poller: ElementPoller) {
+        self.poller = Some(poller);
+    }
+
+    /// Only match elements that are also matched by the specified selector.
+    pub fn add_also(&mut self, by: By<'a>) {
+        self.also.push(by);
+    }
+
     /// Add the specified filter to the list of filters for this selector.
     pub fn add_filter(&mut self, f: ElementPredicate) {
-        self.filters.push(f);
+        self.filters.push(Arc::new(f));
+    }
+
+    //
+    // Builder
+    //
+    // These allow a selector (with filters) to be built independently of any query, and
+    // later passed to `ElementQuery::from_selectors()`. Filters added here ignore errors,
+    // which is the default for ElementQuery.
+    //
+
+    /// Only match elements that also match the specified ElementPredicate.
+    pub fn with_filter(mut self, f: ElementPredicate) -> Self {
+        self.add_filter(f);
+        self
+    }
+
+    /// Use the specified ElementPoller for this selector, rather than the ElementPoller
+    /// for the ElementQuery.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.set_poller(poller);
+        self
+    }
+
+    /// Only match elements that are enabled.
+    pub fn with_enabled(self) -> Self {
+        self.with_filter(conditions::element_is_enabled(true))
+    }
+
+    /// Only match elements that are displayed.
+    pub fn with_displayed(self) -> Self {
+        self.with_filter(conditions::element_is_displayed(true))
+    }
+
+    /// Only match elements that are clickable.
+    pub fn with_clickable(self) -> Self {
+        self.with_filter(conditions::element_is_clickable(true))
+    }
+
+    /// Only match elements that are selected.
+    pub fn with_selected(self) -> Self {
+        self.with_filter(conditions::element_is_selected(true))
+    }
+
+    /// Only match elements that have the specified text.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_text<N>(self, text: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_filter(conditions::element_has_text(text, true))
+    }
+
+    /// Only match elements that have the specified class name.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_class<N>(self, class_name: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_filter(conditions::element_has_class(class_name, true))
+    }
+
+    /// Only match elements that have the specified attribute with the specified value.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_attribute<S, N>(self, attribute_name: S, value: N) -> Self
+    where
+        S: Into<String>,
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_filter(conditions::element_has_attribute(attribute_name, value, true))
     }
 
     //
@@ -80,13 +251,52 @@ impl<'a> ElementSelector<'a> {
     /// Run all filters for this selector on the specified WebElement vec.
     pub async fn run_filters<'b>(
         &self,
-        mut elements: Vec<WebElement<'b>>,
+        elements: Vec<WebElement<'b>>,
     ) -> WebDriverResult<Vec<WebElement<'b>>> {
-        for func in &self.filters {
+        self.run_filters_counted(elements).await.map(|(elements, _)| elements)
+    }
+
+    /// Run all filters for this selector on the specified WebElement vec, and also return
+    /// the number of elements rejected by each filter (in the order the filters were added).
+    pub async fn run_filters_counted<'b>(
+        &self,
+        elements: Vec<WebElement<'b>>,
+    ) -> WebDriverResult<(Vec<WebElement<'b>>, Vec<usize>)> {
+        self.run_filters_buffered(elements, 1).await
+    }
+
+    /// Run all filters for this selector on the specified WebElement vec, evaluating each
+    /// filter on up to `limit` elements at once, and also return the number of elements
+    /// rejected by each filter. Filters still run one after the other, and the order of
+    /// the elements is preserved. A limit of 1 (or 0) evaluates the elements sequentially.
+    pub async fn run_filters_buffered<'b>(
+        &self,
+        mut elements: Vec<WebElement<'b>>,
+        limit: usize,
+    ) -> WebDriverResult<(Vec<WebElement<'b>>, Vec<usize>)> {
+        let mut rejected = vec![0; self.filters.len()];
+        for (index, func) in self.filters.iter().enumerate() {
             let tmp_elements = mem::replace(&mut elements, Vec::new());
-            for element in tmp_elements {
-                if func(&element).await? {
-                    elements.push(element);
+            if limit > 1 {
+                let mut checks = Vec::with_capacity(tmp_elements.len());
+                for element in &tmp_elements {
+                    checks.push(func(element));
+                }
+                let results: Vec<bool> = stream::iter(checks).buffered(limit).try_collect().await?;
+                for (element, matched) in tmp_elements.into_iter().zip(results) {
+                    if matched {
+                        elements.push(element);
+                    } else {
+                        rejected[index] += 1;
+                    }
+                }
+            } else {
+                for element in tmp_elements {
+                    if func(&element).await? {
+                        elements.push(element);
+                    } else {
+                        rejected[index] += 1;
+                    }
                 }
             }
 
@@ -95,7 +305,317 @@ impl<'a> ElementSelector<'a> {
             }
         }
 
-        Ok(elements)
+        Ok((elements, rejected))
+    }
+}
+
+/// Quote the specified string for use as an XPath string literal.
+/// XPath 1.0 has no escape sequences, so strings containing both kinds of quotes
+/// are built using `concat()`.
+fn xpath_literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<String> = value.split('\'').map(|x| format!("'{}'", x)).collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+/// Build an XPath selector that matches any element whose (whitespace-normalized) text
+/// is the specified text. If `exact` is false, elements whose text contains the specified
+/// text will also be matched. Any quotes in the text will be escaped.
+///
+/// Since `By` only borrows its selector string, the XPath is returned as a String that
+/// the caller must keep alive for the duration of the query.
+///
+/// # Example:
+/// ```rust
+/// # use thirtyfour_query::text_xpath;
+/// assert_eq!(text_xpath("Submit", true), "//*[normalize-space(text())='Submit']");
+/// assert_eq!(
+///     text_xpath("Don't", false),
+///     r#"//*[contains(normalize-space(text()), "Don't")]"#
+/// );
+/// ```
+///
+/// The XPath can then be used like this:
+/// ```ignore
+/// let xpath = text_xpath("Submit", true);
+/// let elem = driver.query(By::XPath(&xpath)).first().await?;
+/// ```
+pub fn text_xpath(text: &str, exact: bool) -> String {
+    let literal = xpath_literal(text);
+    if exact {
+        format!("//*[normalize-space(text())={}]", literal)
+    } else {
+        format!("//*[contains(normalize-space(text()), {})]", literal)
+    }
+}
+
+/// Return only the elements in the (half-open) index range `start..end`.
+/// The end of the range is clamped to the number of elements.
+fn slice_elements(mut elements: Vec<WebElement>, start: usize, end: usize) -> Vec<WebElement> {
+    let end = end.min(elements.len());
+    if start >= end {
+        Vec::new()
+    } else {
+        elements.drain(start..end).collect()
+    }
+}
+
+/// Remove any elements that share an element id with an earlier element, preserving the
+/// order of first occurrence.
+fn dedupe_elements(elements: Vec<WebElement>) -> Vec<WebElement> {
+    let mut seen = HashSet::new();
+    elements.into_iter().filter(|x| seen.insert(x.element_id.clone())).collect()
+}
+
+/// If the specified XPath begins with `//`, which searches the whole document even when
+/// the search is started from an element, return it rewritten to begin with `.//` so that
+/// it only searches within that element. Otherwise return None.
+fn element_scoped_xpath(xpath: &str) -> Option<String> {
+    xpath.starts_with("//").then(|| format!(".{}", xpath))
+}
+
+/// Return a copy of the selectors where each selector without filters or `and_also()`
+/// selectors uses find_element(), if `auto_single()` applies to them (see
+/// `ElementQuery::auto_single()`). `enabled` is the value set via `auto_single()`, and
+/// `reorders` is true if `range()` or `sort_by()` is used. Otherwise return None.
+fn auto_single_selectors<'a>(
+    selectors: &[ElementSelector<'a>],
+    enabled: bool,
+    reorders: bool,
+) -> Option<Vec<ElementSelector<'a>>> {
+    if !enabled || reorders {
+        return None;
+    }
+
+    let mut selectors = selectors.to_vec();
+    for selector in &mut selectors {
+        if selector.filters.is_empty() && selector.also.is_empty() {
+            selector.set_single();
+        }
+    }
+    Some(selectors)
+}
+
+/// Convert the index of a filter (numbered across all selectors) in `from` to the index of
+/// the same filter in `to`, where each selector in `from` has the filters of the
+/// corresponding selector in `to`, followed by any extra filters.
+/// Returns None if the filter is one of the extra filters.
+fn map_filter_index(
+    from: &[ElementSelector],
+    to: &[ElementSelector],
+    index: usize,
+) -> Option<usize> {
+    let mut from_offset = 0;
+    let mut to_offset = 0;
+    for (from_selector, to_selector) in from.iter().zip(to) {
+        if index < from_offset + from_selector.filters.len() {
+            let position = index - from_offset;
+            return (position < to_selector.filters.len()).then(|| to_offset + position);
+        }
+        from_offset += from_selector.filters.len();
+        to_offset += to_selector.filters.len();
+    }
+    None
+}
+
+/// Trait for observing when an ElementQuery runs, e.g. to record metrics.
+/// See `ElementQuery::observe()`.
+///
+/// The hooks are called for every retriever that polls for elements, i.e. all except
+/// `exists_all()` and `wait_until_gone()`.
+pub trait QueryObserver: Send + Sync {
+    /// Called when the query starts, with a summary of its selectors.
+    fn on_query_start(&self, selectors: &str);
+
+    /// Called when the query ends, with the number of poll attempts made, the time taken,
+    /// and the number of elements matched. If the query did not match any elements, or
+    /// returned an error, `matched` is 0.
+    fn on_query_end(&self, attempts: u32, elapsed: Duration, matched: usize);
+}
+
+/// The kinds of WebDriverError that can be retried while polling. See
+/// `ElementQuery::retry_on()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebDriverErrorKind {
+    StaleElementReference,
+    InvalidSelector,
+    JavascriptError,
+    NoSuchFrame,
+    NoSuchWindow,
+    UnexpectedAlertOpen,
+    ScriptTimeout,
+    WebDriverTimeout,
+    UnknownError,
+    RequestFailed,
+}
+
+impl WebDriverErrorKind {
+    /// Return true if the specified error is of this kind.
+    pub fn matches(&self, error: &WebDriverError) -> bool {
+        matches!(
+            (self, error),
+            (Self::StaleElementReference, WebDriverError::StaleElementReference(_))
+                | (Self::InvalidSelector, WebDriverError::InvalidSelector(_))
+                | (Self::JavascriptError, WebDriverError::JavascriptError(_))
+                | (Self::NoSuchFrame, WebDriverError::NoSuchFrame(_))
+                | (Self::NoSuchWindow, WebDriverError::NoSuchWindow(_))
+                | (Self::UnexpectedAlertOpen, WebDriverError::UnexpectedAlertOpen(_))
+                | (Self::ScriptTimeout, WebDriverError::ScriptTimeout(_))
+                | (Self::WebDriverTimeout, WebDriverError::WebDriverTimeout(_))
+                | (Self::UnknownError, WebDriverError::UnknownError(_))
+                | (Self::RequestFailed, WebDriverError::RequestFailed(_))
+        )
+    }
+}
+
+/// Statistics about a single run of the poller for an ElementQuery.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    /// The number of poll attempts that were made.
+    pub attempts: u32,
+    /// The total time elapsed while polling.
+    pub elapsed: Duration,
+    /// The number of WebDriver calls made to find elements.
+    pub calls: usize,
+    /// True if the poller stopped because its timeout was reached.
+    pub timed_out: bool,
+    /// True if the poller stopped because the maximum number of calls was reached.
+    pub max_calls_reached: bool,
+    /// The time each poll attempt started, and the number of elements it matched, if
+    /// `ElementQuery::record_attempts()` was used. Otherwise this is empty.
+    pub attempt_log: Vec<(Instant, usize)>,
+}
+
+impl QueryStats {
+    fn new(attempts: u32, start: Instant, calls: usize) -> Self {
+        Self {
+            attempts,
+            elapsed: start.elapsed(),
+            calls,
+            timed_out: false,
+            max_calls_reached: false,
+            attempt_log: Vec::new(),
+        }
+    }
+}
+
+/// The outcome of running each selector once.
+enum PollOutcome<'a> {
+    /// The selector at this index matched these elements.
+    Matched(usize, Vec<WebElement<'a>>),
+    NotMatched,
+    MaxCallsReached,
+}
+
+/// Function signature for the hook called after each unsuccessful poll attempt.
+type RetryHook<'a> = Arc<
+    dyn Fn(u32) -> Pin<Box<dyn Future<Output = WebDriverResult<()>> + Send + 'a>>
+        + Send
+        + Sync
+        + 'a,
+>;
+
+/// The number of elements rejected by each filter, as `(filter index, count)` pairs.
+type FilterDiagnostics = Arc<Mutex<Vec<(usize, usize)>>>;
+
+/// The time each poll attempt started, and the number of elements it matched.
+type AttemptLog = Arc<Mutex<Vec<(Instant, usize)>>>;
+
+/// Function signature for sorting the matched elements.
+type SortFn<'a> = Arc<
+    dyn Fn(
+            Vec<WebElement<'a>>,
+        )
+            -> Pin<Box<dyn Future<Output = WebDriverResult<Vec<WebElement<'a>>>> + Send + 'a>>
+        + Send
+        + Sync
+        + 'a,
+>;
+
+/// Tracks the ElementPollerTicker for each selector in an ElementQuery.
+/// Each selector uses its own ElementPoller if it has one, otherwise the
+/// ElementPoller for the ElementQuery is used.
+struct SelectorTickers {
+    tickers: Vec<Option<ElementPollerTicker>>,
+    timed_out: bool,
+}
+
+impl SelectorTickers {
+    fn new(
+        poller: &ElementPoller,
+        selectors: &[ElementSelector],
+        interval_fn: Option<&IntervalFn>,
+    ) -> Self {
+        let tickers = selectors
+            .iter()
+            .map(|s| {
+                let mut ticker =
+                    ElementPollerTicker::new(s.poller.clone().unwrap_or_else(|| poller.clone()));
+                if let Some(f) = interval_fn {
+                    ticker.set_interval_fn(f.clone());
+                }
+                Some(ticker)
+            })
+            .collect();
+        Self {
+            tickers,
+            timed_out: false,
+        }
+    }
+
+    /// Return true if the selector at the specified index should still be polled.
+    fn is_active(&self, index: usize) -> bool {
+        self.tickers.get(index).map(|x| x.is_some()).unwrap_or(false)
+    }
+
+    /// Record how long the WebDriver call for the selector at the specified index took.
+    fn record_latency(&mut self, index: usize, latency: Duration) {
+        if let Some(Some(ticker)) = self.tickers.get_mut(index) {
+            ticker.record_latency(latency);
+        }
+    }
+
+    /// Stop polling every selector once the specified deadline is reached.
+    fn limit_deadline(&mut self, deadline: Instant) {
+        for ticker in self.tickers.iter_mut().flatten() {
+            ticker.limit_deadline(deadline);
+        }
+    }
+
+    /// Wait for any initial delay required by the poller for each selector.
+    async fn wait_for_delay(&self) {
+        for ticker in self.tickers.iter().flatten() {
+            ticker.wait_for_delay().await;
+        }
+    }
+
+    /// Return true if the poller for any selector stopped because its timeout was reached.
+    fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Advance the ticker for every selector that is still active, without waiting, and
+    /// return the latest of their due times. Returns None once there are no active
+    /// selectors remaining.
+    fn advance(&mut self) -> Option<Instant> {
+        let mut due: Option<Instant> = None;
+        for slot in self.tickers.iter_mut() {
+            if let Some(ticker) = slot {
+                match ticker.advance() {
+                    Some(x) => due = Some(due.map_or(x, |d| d.max(x))),
+                    None => {
+                        self.timed_out |= ticker.timed_out();
+                        *slot = None;
+                    }
+                }
+            }
+        }
+        due
     }
 }
 
@@ -103,9 +623,123 @@ impl<'a> ElementSelector<'a> {
 /// The command issued to the webdriver will differ depending on the source,
 /// i.e. FindElement vs FindElementFromElement etc. but the ElementQuery
 /// interface is the same for both.
+///
+/// Elements can also be queried from within the shadow root of a WebElement.
+/// This uses JavaScript (`shadowRoot.querySelectorAll()`) and therefore only
+/// supports CSS-based selectors (i.e. Id, Name, Tag, ClassName and Css).
+///
+/// Elements can also be queried from the ancestors of a WebElement, nearest first.
+/// This also uses JavaScript, and supports CSS-based selectors as well as XPath.
+/// See `AncestorQueryable` for details.
+///
+/// NOTE: When querying from a WebElement, an XPath selector beginning with `//`
+/// searches the whole document, not just the descendants of the element. Use a relative
+/// XPath (beginning with `.//`) to search within the element, or use
+/// `ElementQuery::relative_xpath()` to rewrite such selectors automatically.
 pub enum ElementQuerySource<'a> {
     Driver(&'a WebDriverSession),
     Element(&'a WebElement<'a>),
+    ShadowRoot(&'a WebElement<'a>),
+    Ancestors(&'a WebElement<'a>),
+}
+
+/// Find all elements matching the specified selector within the shadow root of the
+/// specified element. If the element has no (open) shadow root, no elements are returned.
+async fn find_elements_in_shadow_root<'a>(
+    element: &'a WebElement<'a>,
+    by: By<'a>,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    let selector = by.get_w3c_selector();
+    if selector.name != "css selector" {
+        return Err(WebDriverError::InvalidSelector(WebDriverErrorInfo::new(&format!(
+            "Selector {} is not supported within a shadow root. Use a CSS-based selector instead",
+            by
+        ))));
+    }
+
+    let mut args = ScriptArgs::new();
+    args.push(element)?;
+    args.push(selector.query)?;
+    let ret = element
+        .session
+        .execute_script_with_args(
+            r#"
+            const root = arguments[0].shadowRoot;
+            return root ? Array.from(root.querySelectorAll(arguments[1])) : [];
+            "#,
+            &args,
+        )
+        .await?;
+    let elements: Vec<ElementRef> = ret.convert()?;
+    Ok(elements
+        .into_iter()
+        .map(|x| WebElement::new(element.session, ElementId::from(x.id)))
+        .collect())
+}
+
+/// Find the elements matching the specified XPath from the specified element.
+/// This is used for XPath expressions built at runtime, which do not live as long
+/// as the element itself.
+async fn find_elements_by_xpath<'a>(
+    element: &'a WebElement<'a>,
+    xpath: &str,
+    single: bool,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    let elements = match single {
+        true => element.find_element(By::XPath(xpath)).await.map(|x| vec![x]),
+        false => element.find_elements(By::XPath(xpath)).await,
+    }?;
+    Ok(elements.into_iter().map(|x| WebElement::new(element.session, x.element_id)).collect())
+}
+
+/// Find all ancestors of the specified element that match the specified selector,
+/// ordered from the nearest ancestor (the parent) to the furthest (the root element).
+///
+/// CSS-based selectors are tested against each ancestor using `Element.matches()`.
+/// An XPath selector is evaluated as a step on the ancestor axis, i.e. `By::XPath("div[@role]")`
+/// is evaluated as `ancestor::div[@role]` from the element. Link text selectors are not
+/// supported.
+async fn find_ancestors<'a>(
+    element: &'a WebElement<'a>,
+    by: By<'a>,
+) -> WebDriverResult<Vec<WebElement<'a>>> {
+    let selector = by.get_w3c_selector();
+    let script = match selector.name.as_str() {
+        "css selector" => {
+            r#"
+            const found = [];
+            for (let e = arguments[0].parentElement; e; e = e.parentElement) {
+                if (e.matches(arguments[1])) found.push(e);
+            }
+            return found;
+            "#
+        }
+        "xpath" => {
+            r#"
+            const result = document.evaluate("ancestor::" + arguments[1], arguments[0], null,
+                XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+            const found = [];
+            for (let i = result.snapshotLength - 1; i >= 0; i--) found.push(result.snapshotItem(i));
+            return found;
+            "#
+        }
+        _ => {
+            return Err(WebDriverError::InvalidSelector(WebDriverErrorInfo::new(&format!(
+                "Selector {} is not supported for ancestor queries. Use a CSS or XPath selector instead",
+                by
+            ))));
+        }
+    };
+
+    let mut args = ScriptArgs::new();
+    args.push(element)?;
+    args.push(selector.query)?;
+    let ret = element.session.execute_script_with_args(script, &args).await?;
+    let elements: Vec<ElementRef> = ret.convert()?;
+    Ok(elements
+        .into_iter()
+        .map(|x| WebElement::new(element.session, ElementId::from(x.id)))
+        .collect())
 }
 
 /// High-level interface for performing powerful element queries using a
@@ -136,12 +770,68 @@ pub enum ElementQuerySource<'a> {
 /// #     })
 /// # }
 /// ```
+///
+/// An ElementQuery can be cloned cheaply, so a query with common filters can be built
+/// once and then used as a template for other queries. Each clone keeps its own record
+/// for `last_diagnostics()` and `last_attempt_log()`.
 pub struct ElementQuery<'a> {
     source: Arc<ElementQuerySource<'a>>,
     poller: ElementPoller,
+    interval_fn: Option<IntervalFn>,
     selectors: Vec<ElementSelector<'a>>,
     ignore_errors: bool,
+    ignore_fetch_errors: bool,
+    retry_on: Vec<WebDriverErrorKind>,
     description: String,
+    debug_dump: bool,
+    range: Option<(usize, usize)>,
+    sort: Option<SortFn<'a>>,
+    max_calls: Option<usize>,
+    on_retry: Option<RetryHook<'a>>,
+    find_in_frames: bool,
+    auto_single: bool,
+    diagnostics: Option<FilterDiagnostics>,
+    attempt_log: Option<AttemptLog>,
+    observer: Option<Arc<dyn QueryObserver>>,
+    all_arms: bool,
+    lenient: bool,
+    dedupe: bool,
+    deadline: Option<Instant>,
+    relative_xpath: bool,
+    filter_concurrency: usize,
+}
+
+impl<'a> Clone for ElementQuery<'a> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            poller: self.poller.clone(),
+            interval_fn: self.interval_fn.clone(),
+            selectors: self.selectors.clone(),
+            ignore_errors: self.ignore_errors,
+            ignore_fetch_errors: self.ignore_fetch_errors,
+            retry_on: self.retry_on.clone(),
+            description: self.description.clone(),
+            debug_dump: self.debug_dump,
+            range: self.range,
+            sort: self.sort.clone(),
+            max_calls: self.max_calls,
+            on_retry: self.on_retry.clone(),
+            find_in_frames: self.find_in_frames,
+            auto_single: self.auto_single,
+            // Each clone records its own runs, so that running one query does not
+            // overwrite the record of another.
+            diagnostics: self.diagnostics.as_ref().map(|_| Arc::new(Mutex::new(Vec::new()))),
+            attempt_log: self.attempt_log.as_ref().map(|_| Arc::new(Mutex::new(Vec::new()))),
+            observer: self.observer.clone(),
+            all_arms: self.all_arms,
+            lenient: self.lenient,
+            dedupe: self.dedupe,
+            deadline: self.deadline,
+            relative_xpath: self.relative_xpath,
+            filter_concurrency: self.filter_concurrency,
+        }
+    }
 }
 
 impl<'a> ElementQuery<'a> {
@@ -150,13 +840,42 @@ impl<'a> ElementQuery<'a> {
     //
 
     fn new(source: ElementQuerySource<'a>, poller: ElementPoller, by: By<'a>) -> Self {
-        let selector = ElementSelector::new(by.clone());
+        Self::from_selectors(source, poller, vec![ElementSelector::new(by)])
+    }
+
+    /// Create an ElementQuery from the specified selectors, which may have been built
+    /// (with filters) independently of any query. Further selectors and filters can still
+    /// be added to the query as normal.
+    pub fn from_selectors(
+        source: ElementQuerySource<'a>,
+        poller: ElementPoller,
+        selectors: Vec<ElementSelector<'a>>,
+    ) -> Self {
         Self {
             source: Arc::new(source),
             poller,
-            selectors: vec![selector],
+            interval_fn: None,
+            selectors,
             ignore_errors: true,
+            ignore_fetch_errors: false,
+            retry_on: Vec::new(),
             description: String::new(),
+            debug_dump: false,
+            range: None,
+            sort: None,
+            max_calls: None,
+            on_retry: None,
+            find_in_frames: false,
+            auto_single: false,
+            diagnostics: None,
+            attempt_log: None,
+            observer: None,
+            all_arms: false,
+            lenient: false,
+            dedupe: false,
+            deadline: None,
+            relative_xpath: false,
+            filter_concurrency: 1,
         }
     }
 
@@ -167,18 +886,114 @@ impl<'a> ElementQuery<'a> {
         self
     }
 
-    /// By default a query will ignore any errors that occur while polling for the desired
-    /// element(s). However, this behaviour can be modified so that the waiter will return
-    /// early if an error is returned from thirtyfour.
+    /// By default a query will ignore any errors that occur while running filters on the
+    /// matched element(s). However, this behaviour can be modified so that the query will
+    /// return early if an error is returned from thirtyfour.
+    ///
+    /// This only applies to filters. See `ignore_fetch_errors()` for errors returned while
+    /// finding elements.
     pub fn ignore_errors(mut self, ignore: bool) -> Self {
         self.ignore_errors = ignore;
         self
     }
 
+    /// If true, ignore any errors returned while finding elements (other than NoSuchElement,
+    /// which is never an error while polling), such as StaleElementReference. The selector
+    /// is then treated as not matching for that poll attempt, and polling continues.
+    /// By default these errors end the query immediately. Use `retry_on()` instead to only
+    /// ignore specific kinds of errors.
+    pub fn ignore_fetch_errors(mut self, ignore: bool) -> Self {
+        self.ignore_fetch_errors = ignore;
+        self
+    }
+
+    /// Continue polling if an error of one of the specified kinds is returned while
+    /// finding elements. The selector is then treated as not matching for that poll
+    /// attempt. Errors of any other kind end the query immediately (unless all errors
+    /// are ignored via `ignore_fetch_errors(true)`).
+    ///
+    /// For example, `retry_on(vec![WebDriverErrorKind::StaleElementReference])` keeps
+    /// polling if the parent element is briefly stale, but still fails fast on an
+    /// invalid selector.
+    pub fn retry_on(mut self, kinds: Vec<WebDriverErrorKind>) -> Self {
+        self.retry_on = kinds;
+        self
+    }
+
+    /// If the query fails to match any elements, run each selector once more without filters,
+    /// and log (via `log::warn!()`) the outer HTML of up to 5 elements matched by each selector.
+    ///
+    /// This is useful for figuring out why a selector matches elements but the filters
+    /// reject all of them.
+    pub fn debug_dump_on_error(mut self) -> Self {
+        self.debug_dump = true;
+        self
+    }
+
     //
     // Poller / Waiter
     //
 
+    /// Also search within each iframe on the page, if no elements were found in the
+    /// current frame. On each poll attempt, the driver switches into each iframe in turn
+    /// and runs the selectors, stopping at the first frame that yields a match.
+    ///
+    /// If elements are matched within an iframe, the driver is left switched into that
+    /// frame so that the elements can be used. Call `driver.switch_to().default_content()`
+    /// when you are done with them. If no elements are matched (or an error occurs),
+    /// the driver is switched back to the default content.
+    ///
+    /// NOTE: This only applies to queries from a WebDriver, and only searches iframes
+    /// that are direct children of the page (not nested iframes).
+    pub fn find_in_frames(mut self) -> Self {
+        self.find_in_frames = true;
+        self
+    }
+
+    /// Notify the specified QueryObserver each time this ElementQuery is run.
+    /// This can be used to record metrics such as the duration of each query.
+    pub fn observe(mut self, observer: Arc<dyn QueryObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Record which filters rejected elements, for debugging complex filter chains.
+    /// After running the query, `last_diagnostics()` returns the number of elements
+    /// rejected by each filter on the last poll attempt.
+    pub fn diagnostic(mut self) -> Self {
+        self.diagnostics = Some(Arc::new(Mutex::new(Vec::new())));
+        self
+    }
+
+    /// Return the number of elements rejected by each filter on the last poll attempt,
+    /// as `(filter index, count)` pairs, if `diagnostic()` was used. Filters are numbered
+    /// from zero in the order they were added to this ElementQuery, across all selectors.
+    /// Each element is only counted against the first filter that rejected it, and filters
+    /// that rejected no elements are omitted.
+    ///
+    /// Returns an empty Vec if `diagnostic()` was not used or the query has not been run.
+    pub fn last_diagnostics(&self) -> Vec<(usize, usize)> {
+        self.diagnostics.as_ref().map(|x| x.lock().unwrap().clone()).unwrap_or_default()
+    }
+
+    /// Record the time each poll attempt started and the number of elements it matched,
+    /// for investigating flaky queries without enabling logging. After running the query,
+    /// the record is available from `last_attempt_log()` and from the `attempt_log` of
+    /// the QueryStats returned by `first_with_stats()`.
+    pub fn record_attempts(mut self) -> Self {
+        self.attempt_log = Some(Arc::new(Mutex::new(Vec::new())));
+        self
+    }
+
+    /// Return the time each poll attempt started and the number of elements it matched
+    /// (after filtering, across all selectors checked in that attempt), for the last run
+    /// of this ElementQuery, if `record_attempts()` was used.
+    ///
+    /// Returns an empty Vec if `record_attempts()` was not used or the query has not been run.
+    pub fn last_attempt_log(&self) -> Vec<(Instant, usize)> {
+        self.attempt_log.as_ref().map(|x| x.lock().unwrap().clone()).unwrap_or_default()
+    }
+
     /// Use the specified ElementPoller for this ElementQuery.
     /// This will not affect the default ElementPoller used for other queries.
     pub fn with_poller(mut self, poller: ElementPoller) -> Self {
@@ -186,6 +1001,73 @@ impl<'a> ElementQuery<'a> {
         self
     }
 
+    /// Use the specified function to compute the delay before each poll attempt, instead of
+    /// the fixed interval from the ElementPoller. The function is passed the number of the
+    /// upcoming attempt (starting from 2), so this can be used for exponential backoff or
+    /// any other schedule, e.g. `|attempt| Duration::from_millis(100 * u64::from(attempt))`.
+    ///
+    /// The delay is cut short if it would pass the timeout of the ElementPoller, and the
+    /// number of attempts is still determined by the ElementPoller.
+    pub fn interval_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u32) -> Duration + Send + Sync + 'static,
+    {
+        self.interval_fn = Some(Arc::new(f));
+        self
+    }
+
+    /// Stop polling once the specified deadline is reached, even if the poller's timeout
+    /// (or minimum number of tries) has not been reached yet.
+    ///
+    /// This allows several queries to share one overall time budget, rather than each
+    /// query waiting for its full timeout. Use `deadline_after()` to create a deadline
+    /// that is scaled in the same way as poller timeouts. A deadline of None means no limit.
+    ///
+    /// ## Example:
+    /// ```ignore
+    /// let deadline = deadline_after(Duration::from_secs(10));
+    /// let form = driver.query(By::Id("form")).with_deadline(deadline).first().await?;
+    /// let button = form.query(By::Tag("button")).with_deadline(deadline).first().await?;
+    /// ```
+    pub fn with_deadline(mut self, deadline: impl Into<Option<Instant>>) -> Self {
+        self.deadline = deadline.into();
+        self
+    }
+
+    /// Evaluate each filter on up to `limit` matched elements at once, rather than one at
+    /// a time. Filters still run one after the other, and the order of the matched elements
+    /// is preserved. The default is 1 (sequential), since some drivers do not cope well
+    /// with many simultaneous requests.
+    pub fn filter_concurrency(mut self, limit: usize) -> Self {
+        self.filter_concurrency = limit;
+        self
+    }
+
+    /// Limit the total number of WebDriver calls made to find elements for this ElementQuery,
+    /// across all poll attempts and selectors. Once the limit is reached, polling stops
+    /// regardless of the poller's timeout.
+    pub fn max_calls(mut self, max_calls: usize) -> Self {
+        self.max_calls = Some(max_calls);
+        self
+    }
+
+    /// Call the specified function after each unsuccessful poll attempt that will be
+    /// retried, before waiting for the next one. It is not called after the final attempt.
+    /// The function is passed the number of the attempt that failed (starting from 1). This can be used to perform an action between attempts, such as
+    /// clicking a "load more" button.
+    ///
+    /// If the function returns an error, the query stops and returns that error.
+    pub fn on_retry<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u32) -> Pin<Box<dyn Future<Output = WebDriverResult<()>> + Send + 'a>>
+            + Send
+            + Sync
+            + 'a,
+    {
+        self.on_retry = Some(Arc::new(f));
+        self
+    }
+
     /// Force this ElementQuery to wait for the specified timeout, polling once
     /// after each interval. This will override the poller for this
     /// ElementQuery only.
@@ -217,6 +1099,101 @@ impl<'a> ElementQuery<'a> {
         self.add_selector(ElementSelector::new(by))
     }
 
+    /// Add a new CSS selector to this ElementQuery.
+    /// This is shorthand for `or(By::Css(selector))`.
+    pub fn or_css(self, selector: &'a str) -> Self {
+        self.or(By::Css(selector))
+    }
+
+    /// Add a new XPath selector to this ElementQuery.
+    /// This is shorthand for `or(By::XPath(xpath))`.
+    pub fn or_xpath(self, xpath: &'a str) -> Self {
+        self.or(By::XPath(xpath))
+    }
+
+    /// Add a new Id selector to this ElementQuery.
+    /// This is shorthand for `or(By::Id(id))`.
+    pub fn or_id(self, id: &'a str) -> Self {
+        self.or(By::Id(id))
+    }
+
+    /// Only match elements that are also matched by the specified selector. Unlike `or()`,
+    /// this does not add a new selector. Instead the elements matched by the current
+    /// selector are intersected with those matched by this one (by element id).
+    ///
+    /// For example, `driver.query(By::Tag("button")).and_also(By::Name("submit"))` only
+    /// matches button elements named "submit".
+    pub fn and_also(mut self, by: By<'a>) -> Self {
+        if let Some(selector) = self.selectors.last_mut() {
+            selector.add_also(by);
+        }
+        self
+    }
+
+    /// Add a new selector to this ElementQuery, using the specified ElementPoller for this
+    /// selector only. Once this selector's poller has finished, it will no longer be polled,
+    /// while the remaining selectors continue polling as normal.
+    pub fn or_with_poller(self, by: By<'a>, poller: ElementPoller) -> Self {
+        let mut selector = ElementSelector::new(by);
+        selector.set_poller(poller);
+        self.add_selector(selector)
+    }
+
+    /// Search within the specified element instead of the original source of this
+    /// ElementQuery. This applies to all selectors, including those already added.
+    ///
+    /// This allows a query to be built from the WebDriver and then scoped to a container
+    /// element later.
+    pub fn within(mut self, element: &'a WebElement<'a>) -> Self {
+        self.source = Arc::new(ElementQuerySource::Element(element));
+        self
+    }
+
+    /// If enabled, any XPath selector beginning with `//` is rewritten to begin with `.//`
+    /// when querying from a WebElement, so that it only searches within that element.
+    ///
+    /// By WebDriver's rules, an XPath beginning with `//` searches the whole document even
+    /// when the search is started from an element, which is a common surprise. Without this
+    /// option, such selectors are left as-is, and a warning is logged when the query runs.
+    /// This only affects the main selector of each `query()` / `or()`, not `and_also()`.
+    pub fn relative_xpath(mut self, enabled: bool) -> Self {
+        self.relative_xpath = enabled;
+        self
+    }
+
+    //
+    // Diagnostics
+    //
+
+    /// Return a JSON summary of the configuration of this ElementQuery, for diagnostic
+    /// purposes. This includes the description, the poller, and for each selector the
+    /// `By` (as text), the `single` flag, the selector's own poller (if any) and the number
+    /// of filters. Filters and hooks cannot be serialized, so they are not included.
+    ///
+    /// NOTE: This is not intended for round-trip serialization. Pollers that cannot be
+    /// serialized (e.g. `ElementPoller::UntilInstant`) are represented as `null`.
+    pub fn to_config_json(&self) -> serde_json::Value {
+        let selectors: Vec<serde_json::Value> = self
+            .selectors
+            .iter()
+            .map(|s| {
+                json!({
+                    "by": s.by.to_string(),
+                    "also": s.also.iter().map(|x| x.to_string()).collect::<Vec<String>>(),
+                    "single": s.single,
+                    "poller": s.poller.as_ref().and_then(|x| serde_json::to_value(x).ok()),
+                    "filters": s.filters.len(),
+                })
+            })
+            .collect();
+
+        json!({
+            "description": self.description,
+            "poller": serde_json::to_value(&self.poller).unwrap_or(serde_json::Value::Null),
+            "selectors": selectors,
+        })
+    }
+
     //
     // Retrievers
     //
@@ -227,21 +1204,220 @@ impl<'a> ElementQuery<'a> {
         Ok(!elements.is_empty())
     }
 
+    /// Return true if an element matches any selector within the specified timeout,
+    /// polling once after each interval. Return false if the timeout is reached instead.
+    /// This overrides the poller for this call only.
+    pub async fn exists_within(
+        &self,
+        timeout: Duration,
+        interval: Duration,
+    ) -> WebDriverResult<bool> {
+        let query = self.clone().wait(timeout, interval);
+        let result = query.exists().await;
+        self.copy_results_from(&query);
+        result
+    }
+
+    /// Return true if every selector matches at least one element, otherwise false.
+    /// This does not wait, and all selectors are checked in a single pass.
+    ///
+    /// This differs from calling `exists()` once per selector, since that would check
+    /// each selector at a different point in time (and may wait between them).
+    pub async fn exists_all(&self) -> WebDriverResult<bool> {
+        for selector in &self.selectors {
+            if self.fetch_filtered_elements(selector).await?.is_empty() {
+                return Ok(false);
+            }
+        }
+
+        Ok(!self.selectors.is_empty())
+    }
+
+    /// Wait until no selector matches any element (after filtering), polling once after
+    /// each interval up to the specified timeout. All selectors are checked in a single
+    /// pass on each poll attempt. This overrides the poller for this ElementQuery, but
+    /// hooks such as `on_retry()`, `interval_fn()`, `observe()` and `max_calls()` still apply.
+    ///
+    /// This is useful for waiting until elements such as loading spinners have gone.
+    /// Returns Err(WebDriverError::Timeout) if any element was still matched when the
+    /// timeout was reached.
+    pub async fn wait_until_gone(
+        self,
+        timeout: Duration,
+        interval: Duration,
+    ) -> WebDriverResult<()> {
+        let mut query = self.wait(timeout, interval);
+        query.all_arms = true;
+        let (matched, stats) = query.run_poller_until(|elements| elements.is_empty()).await?;
+        match matched {
+            Some(_) => Ok(()),
+            None => Err(query.poll_error(&stats, "still present")),
+        }
+    }
+
     /// Return true if no element matches any selector, otherwise false.
     pub async fn not_exists(&self) -> WebDriverResult<bool> {
         let elements = self.run_poller(true).await?;
         Ok(elements.is_empty())
     }
 
+    /// Check that no element matches any selector (including all of the filters for that
+    /// selector) for the whole duration of the poller, e.g. that no error banner appears.
+    /// With `ElementPoller::NoWait` the selectors are only checked once.
+    ///
+    /// This differs from `not_exists()` and `wait_until_gone()`, which succeed as soon as
+    /// no element matches. Those are for waiting for something to disappear, whereas this
+    /// confirms that something stays absent, so it always waits for the full timeout when
+    /// it succeeds.
+    ///
+    /// Returns Err(WebDriverError::UnknownError) describing the failed assertion as soon as
+    /// any element matches.
+    pub async fn assert_none(&self) -> WebDriverResult<()> {
+        let (matched, _) = self.run_poller_until(|elements| !elements.is_empty()).await?;
+        match matched {
+            None => Ok(()),
+            Some((_, elements)) => {
+                Err(WebDriverError::UnknownError(WebDriverErrorInfo::new(&format!(
+                    "Assertion failed: {} expected no elements, but found {} using selectors: {}",
+                    get_element_description(&self.description),
+                    elements.len(),
+                    &get_selector_summary(&self.selectors)
+                ))))
+            }
+        }
+    }
+
     /// Return only the first WebElement that matches any selector (including all of
     /// the filters for that selector).
+    ///
+    /// Returns Err(WebDriverError::NoSuchElement) if no elements match, or
+    /// Err(WebDriverError::Timeout) if no elements matched before the poller timed out.
     pub async fn first(&self) -> WebDriverResult<WebElement<'a>> {
-        let mut elements = self.run_poller(false).await?;
+        self.first_with_stats().await.map(|(element, _)| element)
+    }
+
+    /// Return the first WebElement that matches any selector (including all of the filters
+    /// for that selector) and is displayed. If all matched elements are hidden, polling
+    /// continues. The displayed check runs in the same poll attempt, after all other
+    /// filters, so the returned element was displayed when it was selected.
+    /// Elements rejected by the displayed check are not included in `last_diagnostics()`.
+    ///
+    /// Returns Err(WebDriverError::NoSuchElement) if no displayed elements match, or
+    /// Err(WebDriverError::Timeout) if none matched before the poller timed out.
+    pub async fn first_displayed(&self) -> WebDriverResult<WebElement<'a>> {
+        let mut query = self.clone();
+        for selector in &mut query.selectors {
+            selector.add_filter(conditions::element_is_displayed(self.ignore_errors));
+        }
+        let result = query.first().await;
+        self.copy_results_from(&query);
+        result
+    }
+
+    /// Return the only WebElement that matches any selector (including all of the filters
+    /// for that selector). This is useful for checking that a selector is not ambiguous.
+    ///
+    /// Returns Err(WebDriverError::NoSuchElement) or Err(WebDriverError::Timeout) if no
+    /// elements match (as for `first()`), or Err(WebDriverError::InvalidSelector) if more
+    /// than one element matches.
+    pub async fn unique(&self) -> WebDriverResult<WebElement<'a>> {
+        let (mut elements, stats) = self.run_poller_with_stats(false).await?;
+
+        match elements.len() {
+            0 => Err(self.not_found_error(&stats).await),
+            1 => Ok(elements.remove(0)),
+            n => Err(WebDriverError::InvalidSelector(WebDriverErrorInfo::new(&format!(
+                "{} expected exactly 1 element, but found {} using selectors: {}",
+                get_element_description(&self.description),
+                n,
+                &get_selector_summary(&self.selectors)
+            )))),
+        }
+    }
+
+    /// Return the WebElement (from those matched by `all()`) that passes the most of the
+    /// specified criteria. Ties are broken by document order, i.e. the earliest match wins.
+    ///
+    /// Unlike filters, which must all pass, the criteria are optional and only used for
+    /// scoring. This is useful for heuristic selection where no single combination of
+    /// filters is reliable. A candidate is still returned even if it passes no criteria.
+    ///
+    /// Returns Err(WebDriverError::NoSuchElement) or Err(WebDriverError::Timeout) if no
+    /// elements match (as for `first()`).
+    pub async fn best_match(
+        &self,
+        criteria: Vec<ElementPredicate>,
+    ) -> WebDriverResult<WebElement<'a>> {
+        let (elements, stats) = self.run_poller_with_stats(false).await?;
+
+        let mut best: Option<(usize, WebElement<'a>)> = None;
+        for element in elements {
+            let mut score = 0;
+            for f in &criteria {
+                if f(&element).await? {
+                    score += 1;
+                }
+            }
+            if best.as_ref().map(|(x, _)| score > *x).unwrap_or(true) {
+                best = Some((score, element));
+            }
+        }
+
+        match best {
+            Some((_, element)) => Ok(element),
+            None => Err(self.not_found_error(&stats).await),
+        }
+    }
+
+    /// Return only the first WebElement that matches any selector (including all of
+    /// the filters for that selector). If no element is found, run the specified fallback
+    /// (e.g. to click a button that creates the element), and then run the query again.
+    ///
+    /// The fallback is only run if the query returns NoSuchElement or Timeout.
+    /// Any other error (including from the fallback) is returned immediately.
+    pub async fn first_or<F>(&self, fallback: F) -> WebDriverResult<WebElement<'a>>
+    where
+        F: FnOnce() -> Pin<Box<dyn Future<Output = WebDriverResult<()>> + Send + 'a>>,
+    {
+        match self.first().await {
+            Err(WebDriverError::NoSuchElement(_)) | Err(WebDriverError::Timeout(_)) => {
+                fallback().await?;
+                self.first().await
+            }
+            result => result,
+        }
+    }
+
+    /// Return only the first WebElement that matches any selector (including all of
+    /// the filters for that selector), along with the QueryStats for the query.
+    pub async fn first_with_stats(&self) -> WebDriverResult<(WebElement<'a>, QueryStats)> {
+        let single_query = self.auto_single_query();
+        let query = single_query.as_ref().unwrap_or(self);
+        let result = query.run_poller_with_stats(false).await;
+        self.copy_results_from(query);
+        let (mut elements, stats) = result?;
 
         if elements.is_empty() {
-            Err(no_such_element(&self.selectors, &self.description))
+            Err(self.not_found_error(&stats).await)
         } else {
-            Ok(elements.remove(0))
+            Ok((elements.remove(0), stats))
+        }
+    }
+
+    /// Return only the first WebElement that matches any selector (including all of
+    /// the filters for that selector), along with the zero-based index of the selector
+    /// that matched it. Selectors are indexed in the order they were added, starting
+    /// with the selector passed to `query()`.
+    pub async fn first_indexed(&self) -> WebDriverResult<(usize, WebElement<'a>)> {
+        let single_query = self.auto_single_query();
+        let query = single_query.as_ref().unwrap_or(self);
+        let result = query.run_poller_until(|elements| !elements.is_empty()).await;
+        self.copy_results_from(query);
+        let (matched, stats) = result?;
+
+        match matched {
+            Some((index, mut elements)) => Ok((index, elements.remove(0))),
+            None => Err(self.not_found_error(&stats).await),
         }
     }
 
@@ -253,87 +1429,696 @@ impl<'a> ElementQuery<'a> {
         self.run_poller(false).await
     }
 
+    /// Return the WebElements that match every selector (including all of the filters for
+    /// each selector), combined into one Vec.
+    ///
+    /// Unlike `all()`, which returns the matches from whichever selector matched first,
+    /// each poll attempt runs every selector and combines their matches in selector order.
+    /// Elements matched by more than one selector are only included once.
+    /// Polling stops as soon as any selector matches. If `max_calls()` is reached partway
+    /// through a poll attempt, the matches from the selectors that already ran are returned.
+    ///
+    /// Returns an empty Vec if no elements match.
+    pub async fn all_arms(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
+        let mut query = self.clone();
+        query.all_arms = true;
+        let result = query.run_poller(false).await;
+        self.copy_results_from(&query);
+        result
+    }
+
     /// Return all WebElements that match any one selector (including all of the
     /// filters for that selector).
     ///
-    /// Returns Err(WebDriverError::NoSuchElement) if no elements match.
+    /// Returns Err(WebDriverError::NoSuchElement) if no elements match, or
+    /// Err(WebDriverError::Timeout) if no elements matched before the poller timed out.
     pub async fn all_required(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
-        let elements = self.run_poller(false).await?;
+        let (elements, stats) = self.run_poller_with_stats(false).await?;
 
         if elements.is_empty() {
-            Err(no_such_element(&self.selectors, &self.description))
+            Err(self.not_found_error(&stats).await)
         } else {
             Ok(elements)
         }
     }
 
+    /// Return the outer HTML of all WebElements that match any one selector (including all
+    /// of the filters for that selector), in the same order as `all()`.
+    /// The HTML of each element is fetched concurrently.
+    ///
+    /// This is useful for snapshot (golden) testing of the DOM structure.
+    /// Returns an empty Vec if no elements match.
+    pub async fn snapshot(&self) -> WebDriverResult<Vec<String>> {
+        let elements = self.all().await?;
+        try_join_all(elements.iter().map(|x| x.outer_html())).await
+    }
+
+    /// Return the text of all WebElements that match any one selector (including all of
+    /// the filters for that selector), in the same order as `all()`.
+    /// The text of each element is fetched concurrently.
+    ///
+    /// Returns an empty Vec if no elements match.
+    pub async fn collect_texts(&self) -> WebDriverResult<Vec<String>> {
+        let elements = self.all().await?;
+        try_join_all(elements.iter().map(|x| x.text())).await
+    }
+
+    /// Return the value of the specified attribute for all WebElements that match any one
+    /// selector (including all of the filters for that selector), in the same order as
+    /// `all()`. The value is None for elements that do not have the attribute.
+    /// The attribute of each element is fetched concurrently.
+    ///
+    /// Returns an empty Vec if no elements match.
+    pub async fn collect_attributes(&self, name: &str) -> WebDriverResult<Vec<Option<String>>> {
+        let elements = self.all().await?;
+        try_join_all(elements.iter().map(|x| x.get_attribute(name))).await
+    }
+
+    /// Return the result of calling the specified function on each WebElement that
+    /// matches any one selector (including all of the filters for that selector),
+    /// in the same order as `all()`. Elements for which the function returns None are
+    /// dropped. This can be used to extract structured data from the matched elements.
+    ///
+    /// Returns an empty Vec if no elements match.
+    pub async fn map_all<T, F>(&self, f: F) -> WebDriverResult<Vec<T>>
+    where
+        F: for<'b> Fn(
+            &'b WebElement<'b>,
+        )
+            -> Pin<Box<dyn Future<Output = WebDriverResult<Option<T>>> + Send + 'b>>,
+    {
+        let elements = self.all().await?;
+        let mut results = Vec::with_capacity(elements.len());
+        for element in &elements {
+            if let Some(x) = f(element).await? {
+                results.push(x);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Call the specified function on each WebElement that matches any one selector
+    /// (including all of the filters for that selector), in the same order as `all()`.
+    ///
+    /// The function is called for one element at a time, since actions such as clicking
+    /// often change the page. If the function returns an error, no further elements are
+    /// processed and that error is returned.
+    pub async fn each<F>(&self, f: F) -> WebDriverResult<()>
+    where
+        F: for<'b> Fn(
+            &'b WebElement<'b>,
+        ) -> Pin<Box<dyn Future<Output = WebDriverResult<()>> + Send + 'b>>,
+    {
+        for element in &self.all().await? {
+            f(element).await?;
+        }
+        Ok(())
+    }
+
+    /// Wait until exactly the specified number of WebElements match any one selector
+    /// (including all of the filters for that selector), and return them.
+    ///
+    /// Polling continues while the number of matched elements differs from the specified
+    /// count. Returns Err(WebDriverError::Timeout) if the count was not reached before the
+    /// poller timed out, or Err(WebDriverError::NoSuchElement) if the poller does not wait.
+    pub async fn wait_for_count(&self, count: usize) -> WebDriverResult<Vec<WebElement<'a>>> {
+        let (matched, stats) = self.run_poller_until(|elements| elements.len() == count).await?;
+        matched
+            .map(|(_, elements)| elements)
+            .ok_or_else(|| self.poll_error(&stats, &format!("did not reach a count of {}", count)))
+    }
+
+    /// Wait until the WebElements matched by any one selector (including all of the filters
+    /// for that selector) satisfy the specified predicate, and return them.
+    ///
+    /// The predicate sees the full Vec of matched elements for each selector on every poll
+    /// attempt, so it can express conditions that `wait_for_count()` cannot, such as
+    /// "at least 3 elements, in a particular order".
+    /// Returns Err(WebDriverError::Timeout) if the predicate was not satisfied before the
+    /// poller timed out, or Err(WebDriverError::NoSuchElement) if the poller does not wait.
+    pub async fn poll_until<F>(&self, pred: F) -> WebDriverResult<Vec<WebElement<'a>>>
+    where
+        F: Fn(&[WebElement<'a>]) -> bool,
+    {
+        let (matched, stats) = self.run_poller_until(pred).await?;
+        matched
+            .map(|(_, elements)| elements)
+            .ok_or_else(|| self.poll_error(&stats, "did not satisfy the predicate"))
+    }
+
+    /// Return all WebElements that match any selector (including all of the filters for
+    /// that selector), skipping over any selector that returns an error.
+    ///
+    /// Like `all_arms()`, the matches from every successful selector are combined in
+    /// selector order, and elements matched by more than one selector are only included once.
+    /// An error is only returned if every selector failed in the same poll iteration.
+    ///
+    /// Returns an empty Vec if no elements match.
+    pub async fn all_lenient(&self) -> WebDriverResult<Vec<WebElement<'a>>> {
+        let mut query = self.clone();
+        query.all_arms = true;
+        query.lenient = true;
+        let result = query.run_poller(false).await;
+        self.copy_results_from(&query);
+        result
+    }
+
     //
     // Helper Retrievers
     //
 
+    /// Return an error if the ElementPoller for this ElementQuery, or for any selector,
+    /// is invalid.
+    fn poller_validation_error(&self) -> Option<WebDriverError> {
+        self.poller.validation_error().or_else(|| {
+            self.selectors.iter().filter_map(|s| s.poller.as_ref()?.validation_error()).next()
+        })
+    }
+
+    /// If `auto_single()` is enabled, return
+    /// a copy of this ElementQuery that uses find_element() for each selector where that
+    /// cannot change the result of `first()`, i.e. selectors without filters.
+    /// Otherwise return None.
+    fn auto_single_query(&self) -> Option<Self> {
+        let selectors = auto_single_selectors(
+            &self.selectors,
+            self.auto_single,
+            self.range.is_some() || self.sort.is_some(),
+        )?;
+        let mut query = self.clone();
+        query.selectors = selectors;
+        Some(query)
+    }
+
+    /// Log a warning for each XPath selector beginning with `//` in a query from a
+    /// WebElement, since it will search the whole document rather than just the element.
+    fn warn_absolute_xpath(&self) {
+        if self.relative_xpath || !matches!(self.source.as_ref(), ElementQuerySource::Element(_)) {
+            return;
+        }
+        for selector in &self.selectors {
+            if let By::XPath(xpath) = selector.by {
+                if element_scoped_xpath(xpath).is_some() {
+                    warn!(
+                        "XPath({}) searches the whole document, not just the element. Use a \
+                         relative XPath (beginning with .//) or relative_xpath(true) instead",
+                        xpath
+                    );
+                }
+            }
+        }
+    }
+
+    /// Return the error for a query that did not match any elements, logging the
+    /// elements rejected by the filters first if `debug_dump_on_error()` was used.
+    async fn not_found_error(&self, stats: &QueryStats) -> WebDriverError {
+        if self.debug_dump {
+            self.dump_unfiltered_elements().await;
+        }
+        self.poll_error(stats, "not found")
+    }
+
+    /// Return the error for when the matched elements did not satisfy the expected
+    /// condition, where `problem` describes what went wrong. See `poll_error()`.
+    fn poll_error(&self, stats: &QueryStats, problem: &str) -> WebDriverError {
+        poll_error(&self.selectors, &self.description, &self.poller, stats, problem)
+    }
+
+    /// Run each selector once without filters and log the outer HTML of the elements matched.
+    async fn dump_unfiltered_elements(&self) {
+        for selector in &self.selectors {
+            let elements = match self.fetch_elements_from_source(selector).await {
+                Ok(x) => x,
+                Err(WebDriverError::NoSuchElement(_)) => Vec::new(),
+                Err(e) => {
+                    warn!("Selector {} returned an error: {}", selector.by, e);
+                    continue;
+                }
+            };
+
+            if elements.is_empty() {
+                warn!("Selector {} did not match any elements", selector.by);
+                continue;
+            }
+
+            warn!(
+                "Selector {} matched {} element(s), but all were rejected by filters",
+                selector.by,
+                elements.len()
+            );
+            for element in elements.iter().take(DEBUG_DUMP_MAX_ELEMENTS) {
+                match element.outer_html().await {
+                    Ok(html) => warn!("Rejected element: {}", html),
+                    Err(e) => warn!("Rejected element: <unable to get outer HTML: {}>", e),
+                }
+            }
+        }
+    }
+
     /// Run the poller for this ElementQuery and return the Vec of WebElements matched.
     /// NOTE: This function doesn't return a no_such_element error and the caller must handle it.
     async fn run_poller(&self, inverted: bool) -> WebDriverResult<Vec<WebElement<'a>>> {
+        self.run_poller_with_stats(inverted).await.map(|(elements, _)| elements)
+    }
+
+    /// Run the poller for this ElementQuery and return the Vec of WebElements matched,
+    /// along with the QueryStats for this run.
+    /// NOTE: This function doesn't return a no_such_element error and the caller must handle it.
+    async fn run_poller_with_stats(
+        &self,
+        inverted: bool,
+    ) -> WebDriverResult<(Vec<WebElement<'a>>, QueryStats)> {
+        let check = move |elements: &[WebElement<'a>]| {
+            let found = !elements.is_empty();
+            if inverted {
+                !found
+            } else {
+                found
+            }
+        };
+
+        let (matched, stats) = self.run_poller_until(check).await?;
+        Ok((matched.map(|(_, elements)| elements).unwrap_or_default(), stats))
+    }
+
+    /// Run the poller for this ElementQuery until the filtered elements for any one selector
+    /// satisfy the specified check, and return the index of that selector and its elements,
+    /// along with the QueryStats for this run. If the poller times out, None is returned
+    /// instead.
+    async fn run_poller_until<F>(
+        &self,
+        check: F,
+    ) -> WebDriverResult<(Option<(usize, Vec<WebElement<'a>>)>, QueryStats)>
+    where
+        F: Fn(&[WebElement<'a>]) -> bool,
+    {
+        let observer = self.observer.as_ref();
+        if let Some(observer) = observer {
+            observer.on_query_start(&get_selector_summary(&self.selectors));
+        }
+        let start = Instant::now();
+        let mut attempts = 0;
+        let result = self.poll_selectors(check, &mut attempts).await;
+        if let Some(observer) = observer {
+            let matched = match &result {
+                Ok((Some((_, elements)), _)) => elements.len(),
+                _ => 0,
+            };
+            observer.on_query_end(attempts, start.elapsed(), matched);
+        }
+        result
+    }
+
+    /// The implementation of `run_poller_until()`. The number of attempts made so far is
+    /// written to `attempts`, so that it is available even if an error is returned.
+    async fn poll_selectors<F>(
+        &self,
+        check: F,
+        attempts: &mut u32,
+    ) -> WebDriverResult<(Option<(usize, Vec<WebElement<'a>>)>, QueryStats)>
+    where
+        F: Fn(&[WebElement<'a>]) -> bool,
+    {
         let no_such_element_error = no_such_element(&self.selectors, &self.description);
         if self.selectors.is_empty() {
             return Err(no_such_element_error);
         }
-        let mut ticker = ElementPollerTicker::new(self.poller.clone());
+        let start = Instant::now();
+        let mut calls = 0;
+        if let Some(e) = self.poller_validation_error() {
+            return Err(e);
+        }
+        self.warn_absolute_xpath();
+        let mut tickers =
+            SelectorTickers::new(&self.poller, &self.selectors, self.interval_fn.as_ref());
+        if let Some(deadline) = self.deadline {
+            tickers.limit_deadline(deadline);
+        }
+        tickers.wait_for_delay().await;
+
+        if let Some(log) = &self.attempt_log {
+            log.lock().unwrap().clear();
+        }
+        loop {
+            *attempts += 1;
+            self.log_attempt();
+            if let Some(diagnostics) = &self.diagnostics {
+                diagnostics.lock().unwrap().clear();
+            }
+            let mut outcome = self.check_selectors(&mut tickers, &mut calls, &check).await?;
+            if self.find_in_frames {
+                if let PollOutcome::NotMatched = outcome {
+                    outcome = self.check_frames(&mut tickers, &mut calls, &check).await?;
+                }
+            }
+
+            match outcome {
+                PollOutcome::Matched(index, elements) => {
+                    return Ok((Some((index, elements)), self.new_stats(*attempts, start, calls)));
+                }
+                PollOutcome::MaxCallsReached => {
+                    let mut stats = self.new_stats(*attempts, start, calls);
+                    stats.max_calls_reached = true;
+                    return Ok((None, stats));
+                }
+                PollOutcome::NotMatched => {}
+            }
+
+            let due = match tickers.advance() {
+                Some(x) => x,
+                None => {
+                    let mut stats = self.new_stats(*attempts, start, calls);
+                    stats.timed_out = tickers.timed_out();
+                    return Ok((None, stats));
+                }
+            };
+
+            if let Some(f) = &self.on_retry {
+                f(*attempts).await?;
+            }
+            sleep_until(due).await;
+        }
+    }
+
+    /// Run each active selector once, and return the index and elements of the first
+    /// selector whose filtered elements satisfy the specified check.
+    async fn check_selectors<F>(
+        &self,
+        tickers: &mut SelectorTickers,
+        calls: &mut usize,
+        check: &F,
+    ) -> WebDriverResult<PollOutcome<'a>>
+    where
+        F: Fn(&[WebElement<'a>]) -> bool,
+    {
+        let mut union = Vec::new();
+        let mut succeeded = false;
+        let mut last_error = None;
+        let mut max_calls_reached = false;
+        let mut skipped = false;
+        for (index, selector) in self.selectors.iter().enumerate() {
+            if !tickers.is_active(index) {
+                continue;
+            }
+
+            if self.max_calls.map(|max| *calls >= max).unwrap_or(false) {
+                if !self.all_arms {
+                    return Ok(PollOutcome::MaxCallsReached);
+                }
+                // Keep the matches from the selectors that have already run.
+                max_calls_reached = true;
+                break;
+            }
+            *calls += 1;
+
+            let elements = match self.run_selector(tickers, index, selector).await {
+                Ok(Some(x)) => x,
+                Ok(None) => {
+                    skipped = true;
+                    continue;
+                }
+                Err(e) if self.lenient => {
+                    last_error = Some(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            succeeded = true;
+
+            self.log_matches(elements.len());
+
+            if self.all_arms {
+                union.extend(elements);
+                continue;
+            }
+
+            if check(&elements) {
+                return Ok(PollOutcome::Matched(index, elements));
+            }
+        }
+
+        if !succeeded {
+            if let Some(e) = last_error {
+                return Err(e);
+            }
+        }
+
+        if self.all_arms {
+            let elements = dedupe_elements(union);
+            // No matches only counts if every selector was checked in this attempt.
+            let complete = !skipped && !max_calls_reached;
+            if (complete || !elements.is_empty()) && check(&elements) {
+                return Ok(PollOutcome::Matched(0, elements));
+            }
+        }
+
+        if max_calls_reached {
+            return Ok(PollOutcome::MaxCallsReached);
+        }
+        Ok(PollOutcome::NotMatched)
+    }
+
+    /// Fetch the elements for the selector at the specified index, and apply its filters
+    /// along with `dedupe()`, `sort_by()` and `range()`.
+    /// Returns None if the fetch failed with an error that should be retried.
+    async fn run_selector(
+        &self,
+        tickers: &mut SelectorTickers,
+        index: usize,
+        selector: &ElementSelector<'a>,
+    ) -> WebDriverResult<Option<Vec<WebElement<'a>>>> {
+        let fetch_start = Instant::now();
+        let fetched = self.fetch_elements_from_source(selector).await;
+        tickers.record_latency(index, fetch_start.elapsed());
+        let mut elements = match fetched {
+            Ok(x) => x,
+            Err(WebDriverError::NoSuchElement(_)) => Vec::new(),
+            Err(e) if self.is_retryable(&e) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if !elements.is_empty() {
+            let (filtered, rejected) =
+                selector.run_filters_buffered(elements, self.filter_concurrency).await?;
+            elements = filtered;
+            if let Some(diagnostics) = &self.diagnostics {
+                self.record_rejections(diagnostics, index, &rejected);
+            }
+        }
+
+        if self.dedupe && elements.len() > 1 {
+            elements = dedupe_elements(elements);
+        }
+
+        if let Some(sort) = &self.sort {
+            if elements.len() > 1 {
+                elements = sort(elements).await?;
+            }
+        }
+
+        if let Some((range_start, range_end)) = self.range {
+            elements = slice_elements(elements, range_start, range_end);
+        }
+
+        Ok(Some(elements))
+    }
+
+    /// Switch into each iframe on the page in turn and run each active selector once.
+    /// If any selector matches, the driver is left switched into that frame so that the
+    /// matched elements can be used. Otherwise the driver is switched back to the default
+    /// content, including when an error occurs.
+    ///
+    /// This only applies to queries from a WebDriver.
+    async fn check_frames<F>(
+        &self,
+        tickers: &mut SelectorTickers,
+        calls: &mut usize,
+        check: &F,
+    ) -> WebDriverResult<PollOutcome<'a>>
+    where
+        F: Fn(&[WebElement<'a>]) -> bool,
+    {
+        let driver = match self.source.as_ref() {
+            ElementQuerySource::Driver(driver) => *driver,
+            _ => return Ok(PollOutcome::NotMatched),
+        };
+
+        let frames = match driver.find_elements(By::Tag("iframe")).await {
+            Ok(x) => x,
+            Err(WebDriverError::NoSuchElement(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        for frame in &frames {
+            if driver.switch_to().frame_element(frame).await.is_err() {
+                // The frame may have been removed since it was found.
+                driver.switch_to().default_content().await?;
+                continue;
+            }
+
+            let outcome = self.check_selectors(tickers, calls, check).await;
+            if let Ok(PollOutcome::Matched(..)) = outcome {
+                return outcome;
+            }
+
+            driver.switch_to().default_content().await?;
+            if let Ok(PollOutcome::NotMatched) = outcome {
+                continue;
+            }
+            return outcome;
+        }
+
+        Ok(PollOutcome::NotMatched)
+    }
+
+    /// Return true if the specified error (returned while finding elements) should be
+    /// treated as no match for this poll attempt, rather than ending the query.
+    fn is_retryable(&self, error: &WebDriverError) -> bool {
+        self.ignore_fetch_errors || self.retry_on.iter().any(|kind| kind.matches(error))
+    }
+
+    /// Record the start of a poll attempt, if `record_attempts()` was used.
+    fn log_attempt(&self) {
+        if let Some(log) = &self.attempt_log {
+            log.lock().unwrap().push((Instant::now(), 0));
+        }
+    }
 
-        let check = |value: bool| {
-            if inverted {
-                !value
-            } else {
-                value
+    /// Add the specified number of matched elements to the current poll attempt, if
+    /// `record_attempts()` was used.
+    fn log_matches(&self, count: usize) {
+        if let Some(log) = &self.attempt_log {
+            if let Some((_, matched)) = log.lock().unwrap().last_mut() {
+                *matched += count;
             }
-        };
+        }
+    }
 
-        loop {
-            for selector in &self.selectors {
-                let mut elements = match self.fetch_elements_from_source(selector).await {
-                    Ok(x) => x,
-                    Err(WebDriverError::NoSuchElement(_)) => Vec::new(),
-                    Err(e) => return Err(e),
-                };
+    /// Return the QueryStats for the current run, including the attempt log (if any).
+    fn new_stats(&self, attempts: u32, start: Instant, calls: usize) -> QueryStats {
+        let mut stats = QueryStats::new(attempts, start, calls);
+        stats.attempt_log = self.last_attempt_log();
+        stats
+    }
 
-                if !elements.is_empty() {
-                    elements = selector.run_filters(elements).await?;
-                }
+    /// Copy the diagnostics and attempt log from the last run of the specified query, which
+    /// was cloned from this query (possibly with extra filters added to each selector), to
+    /// this query. Rejections by filters that this query does not have are left out, so
+    /// that the filter numbering in `last_diagnostics()` is unaffected.
+    fn copy_results_from(&self, query: &Self) {
+        if let (Some(to), Some(from)) = (&self.diagnostics, &query.diagnostics) {
+            if !Arc::ptr_eq(to, from) {
+                let rejections: Vec<(usize, usize)> = from
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|(index, count)| {
+                        map_filter_index(&query.selectors, &self.selectors, *index)
+                            .map(|x| (x, *count))
+                    })
+                    .collect();
+                *to.lock().unwrap() = rejections;
+            }
+        }
 
-                if check(!elements.is_empty()) {
-                    return Ok(elements);
-                }
+        if let (Some(to), Some(from)) = (&self.attempt_log, &query.attempt_log) {
+            if !Arc::ptr_eq(to, from) {
+                let log = from.lock().unwrap().clone();
+                *to.lock().unwrap() = log;
             }
+        }
+    }
 
-            if !ticker.tick().await {
-                return Ok(Vec::new());
+    /// Add the number of elements rejected by each filter of the selector at the specified
+    /// index to the diagnostics, numbering the filters across all selectors in this query.
+    fn record_rejections(
+        &self,
+        diagnostics: &Mutex<Vec<(usize, usize)>>,
+        selector_index: usize,
+        rejected: &[usize],
+    ) {
+        let offset: usize = self.selectors[..selector_index].iter().map(|s| s.filters.len()).sum();
+        let mut diagnostics = diagnostics.lock().unwrap();
+        for (index, count) in rejected.iter().enumerate().filter(|(_, count)| **count > 0) {
+            let filter_index = offset + index;
+            match diagnostics.iter_mut().find(|(i, _)| *i == filter_index) {
+                Some((_, total)) => *total += count,
+                None => diagnostics.push((filter_index, *count)),
             }
         }
     }
 
+    /// Execute the specified selector once and return the matched WebElements that pass
+    /// all of its filters. NoSuchElement is treated as no elements.
+    async fn fetch_filtered_elements(
+        &self,
+        selector: &ElementSelector<'a>,
+    ) -> WebDriverResult<Vec<WebElement<'a>>> {
+        let elements = match self.fetch_elements_from_source(selector).await {
+            Ok(x) => x,
+            Err(WebDriverError::NoSuchElement(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        if elements.is_empty() {
+            Ok(elements)
+        } else {
+            selector
+                .run_filters_buffered(elements, self.filter_concurrency)
+                .await
+                .map(|(elements, _)| elements)
+        }
+    }
+
     /// Execute the specified selector and return any matched WebElements.
     fn fetch_elements_from_source(
         &self,
         selector: &ElementSelector<'a>,
     ) -> impl Future<Output = WebDriverResult<Vec<WebElement<'a>>>> + Send {
         let by = selector.by.clone();
+        let also = selector.also.clone();
         let single = selector.single;
         let source = self.source.clone();
+        let relative_xpath = match &by {
+            By::XPath(xpath) if self.relative_xpath => element_scoped_xpath(xpath),
+            _ => None,
+        };
         async move {
-            match single {
-                true => match source.as_ref() {
-                    ElementQuerySource::Driver(driver) => {
-                        driver.find_element(by).await.map(|x| vec![x])
-                    }
-                    ElementQuerySource::Element(element) => {
-                        element.find_element(by).await.map(|x| vec![x])
-                    }
-                },
-                false => match source.as_ref() {
-                    ElementQuerySource::Driver(driver) => driver.find_elements(by).await,
-                    ElementQuerySource::Element(element) => element.find_elements(by).await,
+            let mut elements = match (source.as_ref(), relative_xpath) {
+                (ElementQuerySource::Element(element), Some(xpath)) => {
+                    find_elements_by_xpath(element, &xpath, single).await
+                }
+                _ => match single {
+                    true => match source.as_ref() {
+                        ElementQuerySource::Driver(driver) => {
+                            driver.find_element(by).await.map(|x| vec![x])
+                        }
+                        ElementQuerySource::Element(element) => {
+                            element.find_element(by).await.map(|x| vec![x])
+                        }
+                        ElementQuerySource::ShadowRoot(element) => {
+                            find_elements_in_shadow_root(element, by).await
+                        }
+                        ElementQuerySource::Ancestors(element) => find_ancestors(element, by).await,
+                    },
+                    false => find_all_from_source(&source, by).await,
                 },
+            }?;
+
+            for by in also {
+                if elements.is_empty() {
+                    break;
+                }
+
+                let ids: HashSet<ElementId> = match find_all_from_source(&source, by).await {
+                    Ok(x) => x.into_iter().map(|x| x.element_id).collect(),
+                    Err(WebDriverError::NoSuchElement(_)) => HashSet::new(),
+                    Err(e) => return Err(e),
+                };
+                elements.retain(|x| ids.contains(&x.element_id));
             }
+
+            Ok(elements)
         }
     }
 
@@ -342,6 +2127,12 @@ impl<'a> ElementQuery<'a> {
     //
 
     /// Add the specified ElementPredicate to the last selector.
+    ///
+    /// The predicate receives each candidate element, regardless of the source of the
+    /// query. To check for child elements, search from the candidate element (e.g. with
+    /// `elem.find_elements()`) rather than from the WebDriver, so that the search is scoped
+    /// to that element. The same note about relative XPath selectors applies as for
+    /// `ElementQuerySource`.
     pub fn with_filter(mut self, f: ElementPredicate) -> Self {
         if let Some(selector) = self.selectors.last_mut() {
             selector.add_filter(f);
@@ -349,6 +2140,89 @@ impl<'a> ElementQuery<'a> {
         self
     }
 
+    /// Add all of the specified ElementPredicates to the last selector, in order.
+    /// This is useful for applying a list of filters that is stored elsewhere,
+    /// such as in a page object.
+    pub fn with_filters(mut self, filters: Vec<ElementPredicate>) -> Self {
+        if let Some(selector) = self.selectors.last_mut() {
+            for f in filters {
+                selector.add_filter(f);
+            }
+        }
+        self
+    }
+
+    /// Only keep the matched elements in the (half-open) index range `start..end`.
+    /// This is applied after filtering, and a poll attempt only succeeds once the range
+    /// contains at least one element. The end of the range is clamped to the number of
+    /// matched elements.
+    pub fn range(mut self, start: usize, end: usize) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+
+    /// Sort the matched elements by the key returned by the specified function.
+    /// The sort is stable, so elements with equal keys stay in document order.
+    ///
+    /// Sorting runs after filtering (and before `range()`) on each poll attempt, and
+    /// requires at least one extra WebDriver call per element to compute its key.
+    /// For `first()` this returns the element with the lowest key.
+    pub fn sort_by<K, F>(mut self, key_fn: F) -> Self
+    where
+        K: Ord + Send + 'static,
+        F: for<'b> Fn(
+                &'b WebElement<'b>,
+            ) -> Pin<Box<dyn Future<Output = WebDriverResult<K>> + Send + 'b>>
+            + Send
+            + Sync
+            + 'a,
+    {
+        let key_fn = Arc::new(key_fn);
+        self.sort = Some(Arc::new(move |elements| {
+            let key_fn = key_fn.clone();
+            Box::pin(async move {
+                let mut keyed = Vec::with_capacity(elements.len());
+                for element in elements {
+                    let key = key_fn(&element).await?;
+                    keyed.push((key, element));
+                }
+                keyed.sort_by(|a, b| a.0.cmp(&b.0));
+                Ok(keyed.into_iter().map(|(_, element)| element).collect())
+            })
+        }));
+        self
+    }
+
+    /// Sort the matched elements by their text. See `sort_by()` for more details.
+    pub fn sort_by_text(self) -> Self {
+        self.sort_by(|elem| Box::pin(async move { elem.text().await }))
+    }
+
+    /// If enabled, `first()` (and its variants) will automatically use find_element() rather
+    /// than find_elements() for any selector that has no filters, since only the first
+    /// element is needed. This saves the browser from returning every matched element.
+    ///
+    /// This is disabled by default, since find_element() may not return the same element
+    /// as the first element from find_elements() with every driver.
+    ///
+    /// This has no effect if `range()` or `sort_by()` is used, and does not affect other
+    /// methods such as `all()`, which still need every matched element.
+    pub fn auto_single(mut self, enabled: bool) -> Self {
+        self.auto_single = enabled;
+        self
+    }
+
+    /// Remove any matched elements that refer to the same element as an earlier match
+    /// (i.e. that have the same element id), keeping the first occurrence.
+    /// This is applied after filtering and before `sort_by()` and `range()`.
+    ///
+    /// Note that `all_arms()` and `all_lenient()` always remove elements matched by more
+    /// than one selector.
+    pub fn dedupe(mut self) -> Self {
+        self.dedupe = true;
+        self
+    }
+
     /// Set the previous selector to only return the first matched element.
     /// WARNING: Use with caution! This can result in (slightly) faster lookups, but will probably
     ///          break any filters on this selector.
@@ -402,6 +2276,22 @@ impl<'a> ElementQuery<'a> {
         self.with_filter(conditions::element_is_not_displayed(ignore_errors))
     }
 
+    /// Only match elements whose bounding rectangle is at least partially within the
+    /// current viewport. Unlike `and_displayed()`, this excludes elements that are
+    /// rendered but scrolled out of view.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub fn and_displayed_in_viewport(self) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_is_in_viewport(ignore_errors))
+    }
+
+    /// Only match the element that currently has focus.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub fn and_focused(self) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_is_focused(ignore_errors))
+    }
+
     /// Only match elements that are clickable.
     pub fn and_clickable(self) -> Self {
         let ignore_errors = self.ignore_errors;
@@ -420,6 +2310,13 @@ impl<'a> ElementQuery<'a> {
 
     /// Only match elements that have the specified text.
     /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// The Needle must be `'static` because filters are stored as `ElementPredicate`
+    /// closures, which may be cloned and run on every poll attempt (and outlive the
+    /// borrow). Allowing borrowed Needles would mean adding a lifetime to
+    /// `ElementPredicate` and every predicate in `conditions`, which is a breaking change.
+    /// Matching against a `&str` is already supported without cloning up front via
+    /// `exact_text()` and `contains_text()`, which build an owned StringMatch internally.
     pub fn with_text<N>(self, text: N) -> Self
     where
         N: Needle + Clone + Send + Sync + 'static,
@@ -428,6 +2325,52 @@ impl<'a> ElementQuery<'a> {
         self.with_filter(conditions::element_has_text(text, ignore_errors))
     }
 
+    /// Only match elements whose text contains the specified substring.
+    /// This is shorthand for `with_text(StringMatch::new(substring).partial())`.
+    pub fn contains_text(self, substring: &str) -> Self {
+        self.with_text(StringMatch::new(substring).partial())
+    }
+
+    /// Only match elements whose text is exactly the specified text.
+    /// This is shorthand for `with_text(StringMatch::new(text))`.
+    pub fn exact_text(self, text: &str) -> Self {
+        self.with_text(StringMatch::new(text))
+    }
+
+    /// Only match elements whose text contains the specified substring, ignoring case.
+    /// This is shorthand for
+    /// `with_text(StringMatch::new(text).partial().case_insensitive())`.
+    pub fn with_text_ci(self, text: &str) -> Self {
+        self.with_text(StringMatch::new(text).partial().case_insensitive())
+    }
+
+    /// Only match elements that have the specified text, after normalizing whitespace in
+    /// the element's text. Normalizing means trimming leading and trailing whitespace and
+    /// collapsing each run of whitespace (including newlines) into a single space, so
+    /// `"  Save\n  changes "` matches `"Save changes"`. The Needle itself is not normalized.
+    /// Use `with_text()` to match the text exactly as returned by the browser.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_text_normalized<N>(self, text: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_normalized_text(text, ignore_errors))
+    }
+
+    /// Only match elements whose `textContent` has the specified text.
+    /// Unlike `with_text()`, this includes text that is not rendered, such as the text of
+    /// hidden or collapsed elements.
+    /// See the `Needle` documentation for more details on text matching rules.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub fn with_text_content<N>(self, text: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_text_content(text, ignore_errors))
+    }
+
     /// Only match elements that do not have the specified text.
     /// See the `Needle` documentation for more details on text matching rules.
     pub fn without_text<N>(self, text: N) -> Self
@@ -486,6 +2429,25 @@ impl<'a> ElementQuery<'a> {
         self.with_filter(conditions::element_has_class(class_name, ignore_errors))
     }
 
+    /// Only match elements whose class contains the specified substring, ignoring case.
+    /// This is shorthand for
+    /// `with_class(StringMatch::new(class_name).partial().case_insensitive())`.
+    pub fn with_class_ci(self, class_name: &str) -> Self {
+        self.with_class(StringMatch::new(class_name).partial().case_insensitive())
+    }
+
+    /// Only match elements where any one of their class names matches the specified
+    /// class name. Each class name is matched separately, so a full match can be used
+    /// to check for a single class.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_class_token<N>(self, class_name: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_class_token(class_name, ignore_errors))
+    }
+
     /// Only match elements that do not contain the specified class name.
     /// See the `Needle` documentation for more details on text matching rules.
     pub fn without_class<N>(self, class_name: N) -> Self
@@ -557,6 +2519,13 @@ impl<'a> ElementQuery<'a> {
         self.with_filter(conditions::element_has_attribute(attribute_name, value, ignore_errors))
     }
 
+    /// Only match elements that have the specified attribute with a value containing the
+    /// specified substring, ignoring case. This is shorthand for
+    /// `with_attribute(attribute_name, StringMatch::new(value).partial().case_insensitive())`.
+    pub fn with_attribute_ci(self, attribute_name: &str, value: &str) -> Self {
+        self.with_attribute(attribute_name, StringMatch::new(value).partial().case_insensitive())
+    }
+
     /// Only match elements that do not have the specified attribute with the specified value.
     /// See the `Needle` documentation for more details on text matching rules.
     pub fn without_attribute<S, N>(self, attribute_name: S, value: N) -> Self
@@ -568,8 +2537,161 @@ impl<'a> ElementQuery<'a> {
         self.with_filter(conditions::element_lacks_attribute(attribute_name, value, ignore_errors))
     }
 
+    /// Only match elements that have the specified `name` attribute.
+    /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// NOTE: This is the HTML `name` attribute (as used by form fields), not the accessible
+    /// name of the element (e.g. from its label or `aria-label`).
+    pub fn with_name<N>(self, name: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_name(name, ignore_errors))
+    }
+
+    /// Only match elements that have the specified `data-*` attribute, e.g.
+    /// `with_data("row-id", "42")` matches the `data-row-id` attribute.
+    /// The `data-` prefix is added automatically and should not be included in `key`.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_data<N>(self, key: &str, value: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_attribute(format!("data-{}", key), value)
+    }
+
+    /// Only match elements whose `data-testid` attribute is exactly the specified value.
+    /// This is shorthand for `with_data("testid", StringMatch::new(test_id))`.
+    pub fn with_testid(self, test_id: &str) -> Self {
+        self.with_data("testid", StringMatch::new(test_id))
+    }
+
+    /// Only match elements that have a preceding sibling matching the specified selector,
+    /// e.g. the input that follows a particular label.
+    /// See `conditions::element_has_preceding_sibling()` for the supported selectors.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub fn with_preceding_sibling(self, by: By<'_>) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_preceding_sibling(by, ignore_errors))
+    }
+
+    /// Only match elements that have a following sibling matching the specified selector.
+    /// See `conditions::element_has_preceding_sibling()` for the supported selectors.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub fn with_following_sibling(self, by: By<'_>) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_following_sibling(by, ignore_errors))
+    }
+
+    /// Only match elements that have the specified `placeholder` attribute.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_placeholder<N>(self, placeholder: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_attribute("placeholder", placeholder)
+    }
+
+    /// Only match elements that have the specified `title` attribute (e.g. tooltip text).
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_title<N>(self, title: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_attribute("title", title)
+    }
+
+    /// Only match elements whose `href` attribute matches the specified value.
+    /// The attribute is matched as written in the page, so it may be a relative URL.
+    /// Use `with_href_resolved()` to match against the absolute URL instead.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_href<N>(self, href: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_attribute("href", href)
+    }
+
+    /// Only match elements whose absolute `href` URL matches the specified value.
+    /// This uses the `href` property, for which the browser resolves relative URLs
+    /// against the document base URL (normally the current page URL).
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_href_resolved<N>(self, href: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_property("href", href)
+    }
+
+    /// Only match elements whose `src` attribute matches the specified value.
+    /// The attribute is matched as written in the page, so it may be a relative URL.
+    /// Use `with_src_resolved()` to match against the absolute URL instead.
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_src<N>(self, src: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_attribute("src", src)
+    }
+
+    /// Only match elements whose absolute `src` URL matches the specified value.
+    /// This uses the `src` property, for which the browser resolves relative URLs
+    /// against the document base URL (normally the current page URL).
+    /// See the `Needle` documentation for more details on text matching rules.
+    pub fn with_src_resolved<N>(self, src: N) -> Self
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        self.with_property("src", src)
+    }
+
+    /// Only match elements that have the specified attribute, regardless of its value.
+    /// This is useful for boolean attributes such as `disabled` or `checked`.
+    pub fn with_attribute_present(self, attribute_name: &str) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_attribute_present(attribute_name, ignore_errors))
+    }
+
+    /// Only match elements that do not have the specified attribute.
+    pub fn with_attribute_absent(self, attribute_name: &str) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_attribute_absent(attribute_name, ignore_errors))
+    }
+
+    /// Only match elements that have the specified attribute with a value exactly equal
+    /// to any one of the specified values, e.g. `data-state` being "open" or "expanded".
+    /// Elements without the attribute are not matched.
+    pub fn with_attribute_one_of(self, attribute_name: &str, values: Vec<String>) -> Self {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_attribute_one_of(
+            attribute_name,
+            values,
+            ignore_errors,
+        ))
+    }
+
+    /// Only match elements that have the specified attribute with a numeric value that
+    /// satisfies the specified function, e.g. `|x| x > 90.0`.
+    /// Elements where the attribute is missing or not a number are not matched.
+    pub fn with_attribute_number<S, F>(self, attribute_name: S, pred: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(f64) -> bool + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.with_filter(conditions::element_has_attribute_number(
+            attribute_name,
+            pred,
+            ignore_errors,
+        ))
+    }
+
     /// Only match elements that have all of the specified attributes with the specified values.
     /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// The attributes are cloned into the filter, so the slice does not need to be `'static`
+    /// and can be built at runtime (e.g. from a `Vec`).
     pub fn with_attributes<S, N>(self, desired_attributes: &[(S, N)]) -> Self
     where
         S: Into<String> + Clone,
@@ -614,6 +2736,9 @@ impl<'a> ElementQuery<'a> {
 
     /// Only match elements that have all of the specified properties with the specified value.
     /// See the `Needle` documentation for more details on text matching rules.
+    ///
+    /// The properties are cloned into the filter, so the slice does not need to be `'static`
+    /// and can be built at runtime (e.g. from a `Vec`).
     pub fn with_properties<S, N>(self, desired_properties: &[(S, N)]) -> Self
     where
         S: Into<String> + Clone,
@@ -698,6 +2823,18 @@ impl<'a> ElementQuery<'a> {
 /// Trait for enabling the ElementQuery interface.
 pub trait ElementQueryable {
     fn query<'a>(&'a self, by: By<'a>) -> ElementQuery<'a>;
+
+    /// Return an ElementQuery for the specified CSS selector.
+    /// This is shorthand for `query(By::Css(selector))`.
+    fn query_css<'a>(&'a self, selector: &'a str) -> ElementQuery<'a> {
+        self.query(By::Css(selector))
+    }
+
+    /// Return an ElementQuery for the specified XPath.
+    /// This is shorthand for `query(By::XPath(xpath))`.
+    fn query_xpath<'a>(&'a self, xpath: &'a str) -> ElementQuery<'a> {
+        self.query(By::XPath(xpath))
+    }
 }
 
 impl ElementQueryable for WebElement<'_> {
@@ -718,6 +2855,55 @@ impl ElementQueryable for WebDriver {
     }
 }
 
+impl ElementQueryable for WebDriverSession {
+    /// Return an ElementQuery instance for more executing powerful element queries.
+    fn query<'a>(&'a self, by: By<'a>) -> ElementQuery<'a> {
+        let poller: ElementPoller =
+            self.config().get("ElementPoller").unwrap_or(ElementPoller::NoWait);
+        ElementQuery::new(ElementQuerySource::Driver(self), poller, by)
+    }
+}
+
+/// Trait for enabling the ElementQuery interface within the shadow root of an element.
+pub trait ShadowRootQueryable {
+    fn query_shadow<'a>(&'a self, by: By<'a>) -> ElementQuery<'a>;
+}
+
+impl ShadowRootQueryable for WebElement<'_> {
+    /// Return an ElementQuery instance for executing powerful element queries within
+    /// the shadow root of this element.
+    fn query_shadow<'a>(&'a self, by: By<'a>) -> ElementQuery<'a> {
+        let poller: ElementPoller =
+            self.session.config().get("ElementPoller").unwrap_or(ElementPoller::NoWait);
+        ElementQuery::new(ElementQuerySource::ShadowRoot(self), poller, by)
+    }
+}
+
+/// Trait for enabling the ElementQuery interface on the ancestors of an element.
+///
+/// This is the upward counterpart to `ElementQueryable`, and is useful for finding the
+/// row or container that encloses an element without writing `./ancestor::` XPath by hand.
+/// Matches are ordered from the nearest ancestor to the furthest, so `first()` returns
+/// the closest matching ancestor.
+///
+/// CSS-based selectors (Id, Name, Tag, ClassName and Css) support the full CSS selector
+/// syntax. An XPath selector is evaluated as a step on the ancestor axis, so it should be
+/// a node test with optional predicates, e.g. `By::XPath("tr[@data-id]")`.
+/// Link text selectors are not supported and return Err(WebDriverError::InvalidSelector).
+pub trait AncestorQueryable {
+    fn query_ancestor<'a>(&'a self, by: By<'a>) -> ElementQuery<'a>;
+}
+
+impl AncestorQueryable for WebElement<'_> {
+    /// Return an ElementQuery instance for executing powerful element queries against
+    /// the ancestors of this element.
+    fn query_ancestor<'a>(&'a self, by: By<'a>) -> ElementQuery<'a> {
+        let poller: ElementPoller =
+            self.session.config().get("ElementPoller").unwrap_or(ElementPoller::NoWait);
+        ElementQuery::new(ElementQuerySource::Ancestors(self), poller, by)
+    }
+}
+
 #[cfg(test)]
 /// This function checks if the public async methods implement Send. It is not intended to be executed.
 async fn _test_is_send() -> WebDriverResult<()> {
@@ -730,6 +2916,8 @@ async fn _test_is_send() -> WebDriverResult<()> {
     // ElementSelector
     let selector = ElementSelector::new(By::Css("div"));
     is_send_val(&selector.run_filters(Vec::new()));
+    is_send_val(&selector.run_filters_counted(Vec::new()));
+    is_send_val(&selector.run_filters_buffered(Vec::new(), 4));
 
     // Pre values
     let caps = DesiredCapabilities::chrome();
@@ -738,10 +2926,161 @@ async fn _test_is_send() -> WebDriverResult<()> {
     // ElementQuery
     let query = driver.query(By::Css("div"));
     is_send_val(&query.exists());
+    is_send_val(&query.exists_within(Duration::from_secs(1), Duration::from_millis(100)));
+    is_send_val(&query.exists_all());
     is_send_val(&query.not_exists());
+    is_send_val(&query.clone().wait_until_gone(Duration::from_secs(1), Duration::from_millis(100)));
     is_send_val(&query.first());
+    is_send_val(&query.first_with_stats());
+    is_send_val(&query.unique());
+    is_send_val(&query.first_displayed());
+    is_send_val(&query.first_or(|| Box::pin(async { Ok(()) })));
+    is_send_val(&query.first_indexed());
     is_send_val(&query.all());
     is_send_val(&query.all_required());
+    is_send_val(&query.collect_texts());
+    is_send_val(&query.collect_attributes("href"));
+    is_send_val(&query.map_all(|e| Box::pin(async move { e.id().await })));
+    is_send_val(&query.each(|e| Box::pin(e.click())));
+    is_send_val(&query.all_lenient());
+    is_send_val(&query.all_arms());
+    is_send_val(&query.clone().record_attempts().first_with_stats());
+    is_send_val(&query.assert_none());
+    is_send_val(&query.clone().filter_concurrency(4).all());
+    is_send_val(&query.clone().relative_xpath(true).all());
+    is_send_val(&query.snapshot());
+    is_send_val(&query.best_match(vec![conditions::element_is_displayed(true)]));
+    is_send_val(&query.clone().dedupe().all());
+    is_send_val(
+        &query.clone().with_deadline(crate::deadline_after(Duration::from_secs(1))).first(),
+    );
+    is_send_val(&query.wait_for_count(1));
+    is_send_val(&query.poll_until(|elements| elements.len() >= 3));
+
+    // ElementQuery from prebuilt selectors
+    let selector = ElementSelector::new(By::Css("div")).with_displayed().with_text("text");
+    let query = ElementQuery::from_selectors(
+        ElementQuerySource::Driver(&driver.session),
+        ElementPoller::NoWait,
+        vec![selector],
+    );
+    is_send_val(&query.first());
+
+    // ElementQuery rescoped to an element
+    let elem = driver.find_element(By::Css("div")).await?;
+    let query = driver.query(By::Css("div")).within(&elem);
+    is_send_val(&query.first());
+
+    // ElementQuery from a session
+    let query = driver.session.query(By::Css("div"));
+    is_send_val(&query.first());
+
+    // ElementQuery within iframes
+    let query = driver.query(By::Css("div")).find_in_frames();
+    is_send_val(&query.first());
+
+    // ElementQuery with diagnostics
+    let query = driver.query(By::Css("div")).with_text("text").diagnostic();
+    is_send_val(&query.first());
+
+    // ElementQuery with sorting
+    let query = driver.query(By::Css("div")).sort_by_text();
+    is_send_val(&query.all());
+
+    // ElementQuery from selector strings
+    let query = driver.query_css("div").or_xpath("//span").or_id("id").or_css("p");
+    is_send_val(&query.first());
+
+    // ElementQuery with intersected selectors
+    let query = driver.query(By::Css("div")).and_also(By::Name("div"));
+    is_send_val(&query.first());
+
+    // ElementQuery within shadow root
+    let elem = driver.find_element(By::Css("div")).await?;
+    let query = elem.query_shadow(By::Css("div"));
+    is_send_val(&query.first());
+
+    // ElementQuery from ancestors
+    let query = elem.query_ancestor(By::Css("tr"));
+    is_send_val(&query.first());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn singles(selectors: &[ElementSelector]) -> Vec<bool> {
+        selectors.iter().map(|x| x.single).collect()
+    }
+
+    #[test]
+    fn test_auto_single_one_selector() {
+        let selectors = vec![ElementSelector::new(By::Css("div"))];
+        let result = auto_single_selectors(&selectors, true, false).unwrap();
+        assert_eq!(singles(&result), vec![true]);
+        assert!(!selectors[0].single);
+    }
+
+    #[test]
+    fn test_auto_single_filters_and_also() {
+        let filtered = ElementSelector::new(By::Css("div")).with_displayed();
+        let result = auto_single_selectors(&[filtered], true, false).unwrap();
+        assert_eq!(singles(&result), vec![false]);
+
+        let mut also = ElementSelector::new(By::Css("div"));
+        also.add_also(By::ClassName("item"));
+        let result = auto_single_selectors(&[also], true, false).unwrap();
+        assert_eq!(singles(&result), vec![false]);
+    }
+
+    #[test]
+    fn test_auto_single_range_or_sort() {
+        let selectors = vec![ElementSelector::new(By::Css("div"))];
+        assert!(auto_single_selectors(&selectors, true, true).is_none());
+    }
+
+    #[test]
+    fn test_auto_single_multiple_selectors() {
+        let mut also = ElementSelector::new(By::Css("span"));
+        also.add_also(By::ClassName("item"));
+        let selectors = vec![ElementSelector::new(By::Css("div")), also];
+        let result = auto_single_selectors(&selectors, true, false).unwrap();
+        assert_eq!(singles(&result), vec![true, false]);
+    }
+
+    #[test]
+    fn test_element_scoped_xpath() {
+        assert_eq!(element_scoped_xpath("//div"), Some(String::from(".//div")));
+        assert_eq!(
+            element_scoped_xpath("//div[@id='a']//span"),
+            Some(String::from(".//div[@id='a']//span"))
+        );
+        assert_eq!(element_scoped_xpath(".//div"), None);
+        assert_eq!(element_scoped_xpath("./span"), None);
+        assert_eq!(element_scoped_xpath("span"), None);
+    }
+
+    #[test]
+    fn test_map_filter_index() {
+        let original = vec![
+            ElementSelector::new(By::Css("div")).with_enabled(),
+            ElementSelector::new(By::Css("span")).with_enabled().with_displayed(),
+        ];
+        let extended: Vec<_> = original.iter().map(|x| x.clone().with_displayed()).collect();
+        assert_eq!(map_filter_index(&extended, &original, 0), Some(0));
+        assert_eq!(map_filter_index(&extended, &original, 1), None);
+        assert_eq!(map_filter_index(&extended, &original, 2), Some(1));
+        assert_eq!(map_filter_index(&extended, &original, 3), Some(2));
+        assert_eq!(map_filter_index(&extended, &original, 4), None);
+        assert_eq!(map_filter_index(&extended, &original, 5), None);
+        assert_eq!(map_filter_index(&original, &original, 2), Some(2));
+    }
+
+    #[test]
+    fn test_auto_single_disabled() {
+        let selectors = vec![ElementSelector::new(By::Css("div"))];
+        assert!(auto_single_selectors(&selectors, false, false).is_none());
+    }
+}
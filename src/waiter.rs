@@ -1,17 +1,52 @@
-use crate::conditions::handle_errors;
 use crate::{conditions, ElementPoller, ElementPollerTicker, ElementPredicate};
-use std::time::Duration;
-use stringmatch::Needle;
-use thirtyfour::error::WebDriverError;
+use futures::Future;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use stringmatch::{Needle, StringMatch};
+use thirtyfour::error::{WebDriverError, WebDriverErrorInfo};
 use thirtyfour::prelude::WebDriverResult;
-use thirtyfour::WebElement;
+use thirtyfour::support::sleep;
+use thirtyfour::{By, ElementRect, WebDriverCommands, WebElement};
 
-#[derive(Debug, Clone)]
+/// Function signature for lazily building the timeout message.
+type MessageFn<'a> = Arc<dyn Fn() -> String + Send + Sync + 'a>;
+
+/// The maximum difference (in pixels) between two element rects that are considered equal.
+const RECT_EPSILON: f32 = 0.5;
+
+/// Return true if the two rects are equal, allowing for sub-pixel rounding.
+fn rect_equals(a: &ElementRect, b: &ElementRect) -> bool {
+    (a.x - b.x).abs() <= RECT_EPSILON
+        && (a.y - b.y).abs() <= RECT_EPSILON
+        && (a.width - b.width).abs() <= RECT_EPSILON
+        && (a.height - b.height).abs() <= RECT_EPSILON
+}
+
+#[derive(Clone)]
 pub struct ElementWaiter<'a> {
     element: &'a WebElement<'a>,
     poller: ElementPoller,
     message: String,
+    message_fn: Option<MessageFn<'a>>,
     ignore_errors: bool,
+    refetch_by: Option<By<'a>>,
+    initial_delay: Option<Duration>,
+}
+
+impl fmt::Debug for ElementWaiter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementWaiter")
+            .field("element", &self.element)
+            .field("poller", &self.poller)
+            .field("message", &self.message)
+            .field("message_fn", &self.message_fn.as_ref().map(|_| "Fn() -> String"))
+            .field("ignore_errors", &self.ignore_errors)
+            .field("refetch_by", &self.refetch_by)
+            .field("initial_delay", &self.initial_delay)
+            .finish()
+    }
 }
 
 impl<'a> ElementWaiter<'a> {
@@ -20,7 +55,10 @@ impl<'a> ElementWaiter<'a> {
             element,
             poller,
             message: String::new(),
+            message_fn: None,
             ignore_errors: true,
+            refetch_by: None,
+            initial_delay: None,
         }
     }
 
@@ -37,6 +75,28 @@ impl<'a> ElementWaiter<'a> {
         self
     }
 
+    /// Override the error message to be returned in the case of timeout.
+    /// Unlike `error()`, this also replaces any function provided via `with_message_fn()`,
+    /// so it can be used to give a more specific message for a particular condition, e.g.
+    /// `waiter.message("button never enabled").enabled()`.
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = message.into();
+        self.message_fn = None;
+        self
+    }
+
+    /// Provide a function to build the error message to be returned in the case of timeout.
+    /// The function is only called if the timeout actually occurs, which avoids building
+    /// expensive diagnostic messages when the condition passes.
+    /// This takes precedence over any message provided via `error()`.
+    pub fn with_message_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'a,
+    {
+        self.message_fn = Some(Arc::new(f));
+        self
+    }
+
     /// By default a waiter will ignore any errors that occur while polling for the desired
     /// condition(s). However, this behaviour can be modified so that the waiter will return
     /// early if an error is returned from thirtyfour.
@@ -45,21 +105,74 @@ impl<'a> ElementWaiter<'a> {
         self
     }
 
+    /// If the element becomes stale while waiting (e.g. because the page re-rendered it),
+    /// find it again using the specified selector and continue waiting on the new element.
+    /// The selector is run from the session (i.e. the whole page).
+    ///
+    /// Staleness is detected either from a StaleElementReference error, or by checking
+    /// whether the element is still present after each unsuccessful poll attempt (which
+    /// requires one extra WebDriver call per attempt).
+    ///
+    /// NOTE: This cannot be used with `condition_get()` or the other `*_get()` methods.
+    pub fn refetch_with(mut self, by: By<'a>) -> Self {
+        self.refetch_by = Some(by);
+        self
+    }
+
+    /// Sleep for the specified duration before the first poll attempt, e.g. to let an
+    /// animation that was just triggered settle before checking the element.
+    /// The delay counts towards the timeout of the ElementPoller.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = Some(delay);
+        self
+    }
+
     /// Force this ElementWaiter to wait for the specified timeout, polling once
     /// after each interval. This will override the poller for this
     /// ElementWaiter only.
+    ///
+    /// Since the condition is only chosen by the final method call, this can be used to
+    /// adjust the timing for a particular condition, e.g.
+    /// `elem.wait_until().wait(Duration::from_secs(30), interval).clickable()`.
     pub fn wait(self, timeout: Duration, interval: Duration) -> Self {
         self.with_poller(ElementPoller::TimeoutWithInterval(timeout, interval))
     }
 
+    /// Create the ticker for this ElementWaiter and wait for any initial delay,
+    /// including the one set via `initial_delay()`.
+    async fn start_ticker(&self) -> ElementPollerTicker {
+        let ticker = ElementPollerTicker::new(self.poller.clone());
+        ticker.wait_for_delay().await;
+        if let Some(delay) = self.initial_delay {
+            sleep(delay).await;
+        }
+        ticker
+    }
+
     async fn run_poller(&self, conditions: Vec<ElementPredicate>) -> WebDriverResult<bool> {
-        let mut ticker = ElementPollerTicker::new(self.poller.clone());
+        if let Some(e) = self.poller.validation_error() {
+            return Err(e);
+        }
+        let mut ticker = self.start_ticker().await;
+        let mut refetched: Option<WebElement<'a>> = None;
         loop {
+            let attempt_start = Instant::now();
+            let element = refetched.as_ref().unwrap_or(self.element);
             let mut conditions_met = true;
+            let mut stale = false;
             for f in &conditions {
-                if !f(&self.element).await? {
-                    conditions_met = false;
-                    break;
+                match f(element).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        conditions_met = false;
+                        break;
+                    }
+                    Err(WebDriverError::StaleElementReference(_)) if self.refetch_by.is_some() => {
+                        conditions_met = false;
+                        stale = true;
+                        break;
+                    }
+                    Err(e) => return Err(e),
                 }
             }
 
@@ -67,14 +180,40 @@ impl<'a> ElementWaiter<'a> {
                 return Ok(true);
             }
 
+            ticker.record_latency(attempt_start.elapsed());
+
+            if let Some(by) = &self.refetch_by {
+                let present = if stale {
+                    false
+                } else {
+                    match element.is_present().await {
+                        Ok(x) => x,
+                        Err(_) if self.ignore_errors => false,
+                        Err(e) => return Err(e),
+                    }
+                };
+                if !present {
+                    match element.session.find_element(by.clone()).await {
+                        Ok(x) => refetched = Some(x),
+                        Err(WebDriverError::NoSuchElement(_)) => {}
+                        Err(_) if self.ignore_errors => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+
             if !ticker.tick().await {
                 return Ok(false);
             }
         }
     }
 
-    fn timeout(self) -> WebDriverResult<()> {
-        Err(WebDriverError::Timeout(self.message))
+    fn timeout<T>(self) -> WebDriverResult<T> {
+        let message = match &self.message_fn {
+            Some(f) => f(),
+            None => self.message,
+        };
+        Err(WebDriverError::Timeout(message))
     }
 
     pub async fn condition(self, f: ElementPredicate) -> WebDriverResult<()> {
@@ -91,14 +230,132 @@ impl<'a> ElementWaiter<'a> {
         }
     }
 
+    /// Wait until the specified condition is met, and return the element, so that it can
+    /// be used in the same expression, e.g.
+    /// `elem.wait_until().clickable_get().await?.click().await?`.
+    ///
+    /// NOTE: This cannot be combined with `refetch_with()`, because the element found again
+    /// is not the element this ElementWaiter was created for. A WebDriverError::InvalidArgument
+    /// error is returned if `refetch_with()` was used.
+    pub async fn condition_get(self, f: ElementPredicate) -> WebDriverResult<&'a WebElement<'a>> {
+        if let Some(by) = &self.refetch_by {
+            return Err(WebDriverError::InvalidArgument(WebDriverErrorInfo::new(&format!(
+                "condition_get() cannot be used with refetch_with({})",
+                by
+            ))));
+        }
+        let element = self.element;
+        self.condition(f).await?;
+        Ok(element)
+    }
+
+    /// Wait until the element is displayed, and return the element.
+    /// See `condition_get()` for details.
+    pub async fn displayed_get(self) -> WebDriverResult<&'a WebElement<'a>> {
+        let ignore_errors = self.ignore_errors;
+        self.condition_get(conditions::element_is_displayed(ignore_errors)).await
+    }
+
+    /// Wait until the element is enabled, and return the element.
+    /// See `condition_get()` for details.
+    pub async fn enabled_get(self) -> WebDriverResult<&'a WebElement<'a>> {
+        let ignore_errors = self.ignore_errors;
+        self.condition_get(conditions::element_is_enabled(ignore_errors)).await
+    }
+
+    /// Wait until the element is clickable, and return the element.
+    /// See `condition_get()` for details.
+    pub async fn clickable_get(self) -> WebDriverResult<&'a WebElement<'a>> {
+        let ignore_errors = self.ignore_errors;
+        self.condition_get(conditions::element_is_clickable(ignore_errors)).await
+    }
+
+    /// Wait until the text of the element stops changing, i.e. until two successive poll
+    /// attempts read the same text, and return that text.
+    ///
+    /// NOTE: This requires at least two poll attempts, so it always times out if the
+    /// poller only makes a single attempt (e.g. `ElementPoller::NoWait`).
+    pub async fn text_stabilizes(self) -> WebDriverResult<String> {
+        if let Some(e) = self.poller.validation_error() {
+            return Err(e);
+        }
+        let mut ticker = self.start_ticker().await;
+        let mut previous: Option<String> = None;
+        loop {
+            let attempt_start = Instant::now();
+            let result = self.element.text().await;
+            ticker.record_latency(attempt_start.elapsed());
+            match result {
+                Ok(text) => {
+                    if previous.as_ref() == Some(&text) {
+                        return Ok(text);
+                    }
+                    previous = Some(text);
+                }
+                Err(_) if self.ignore_errors => previous = None,
+                Err(e) => return Err(e),
+            }
+
+            if !ticker.tick().await {
+                return self.timeout();
+            }
+        }
+    }
+
+    /// Wait until the position and size of the element stop changing, i.e. until two
+    /// successive poll attempts read the same rectangle (allowing for sub-pixel rounding).
+    /// This is useful for waiting until a CSS transition or animation has finished.
+    ///
+    /// NOTE: This requires at least two poll attempts, so it always times out if the
+    /// poller only makes a single attempt (e.g. `ElementPoller::NoWait`).
+    pub async fn rect_stabilizes(self) -> WebDriverResult<()> {
+        if let Some(e) = self.poller.validation_error() {
+            return Err(e);
+        }
+        let mut ticker = self.start_ticker().await;
+        let mut previous: Option<ElementRect> = None;
+        loop {
+            let attempt_start = Instant::now();
+            let result = self.element.rect().await;
+            ticker.record_latency(attempt_start.elapsed());
+            match result {
+                Ok(rect) => {
+                    if previous.as_ref().map(|x| rect_equals(x, &rect)).unwrap_or(false) {
+                        return Ok(());
+                    }
+                    previous = Some(rect);
+                }
+                Err(_) if self.ignore_errors => previous = None,
+                Err(e) => return Err(e),
+            }
+
+            if !ticker.tick().await {
+                return self.timeout();
+            }
+        }
+    }
+
+    /// Wait until any one of the specified conditions is met. On each poll attempt, the
+    /// conditions are checked in order, and the wait succeeds as soon as one returns true.
+    pub async fn any_condition(self, conditions: Vec<ElementPredicate>) -> WebDriverResult<()> {
+        self.condition(conditions::any_of(conditions)).await
+    }
+
+    /// Wait until the element is no longer present in the DOM.
+    /// This is an alias for `not_present()`.
     pub async fn stale(self) -> WebDriverResult<()> {
+        self.not_present().await
+    }
+
+    /// Wait until the element is no longer present in the DOM.
+    pub async fn not_present(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
-        self.condition(Box::new(move |elem| {
-            Box::pin(
-                async move { handle_errors(elem.is_present().await.map(|x| !x), ignore_errors) },
-            )
-        }))
-        .await
+        self.condition(conditions::element_is_not_present(ignore_errors)).await
+    }
+
+    pub async fn present(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_present(ignore_errors)).await
     }
 
     pub async fn displayed(self) -> WebDriverResult<()> {
@@ -106,6 +363,24 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_is_displayed(ignore_errors)).await
     }
 
+    /// Wait until the element is displayed, then call the specified function with the
+    /// element and return its result.
+    ///
+    /// ## Example:
+    /// ```ignore
+    /// let text = elem.wait_until().displayed_then(|e| Box::pin(e.text())).await?;
+    /// ```
+    pub async fn displayed_then<F, T>(self, f: F) -> WebDriverResult<T>
+    where
+        F: FnOnce(
+            &'a WebElement<'a>,
+        ) -> Pin<Box<dyn Future<Output = WebDriverResult<T>> + Send + 'a>>,
+    {
+        let element = self.element;
+        self.displayed().await?;
+        f(element).await
+    }
+
     pub async fn not_displayed(self) -> WebDriverResult<()> {
         let ignore_errors = self.ignore_errors;
         self.condition(conditions::element_is_not_displayed(ignore_errors)).await
@@ -141,6 +416,46 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_is_not_clickable(ignore_errors)).await
     }
 
+    /// Wait until the element is both enabled and clickable.
+    /// Both conditions are checked together in the same poll iteration.
+    pub async fn enabled_and_clickable(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.conditions(vec![
+            conditions::element_is_enabled(ignore_errors),
+            conditions::element_is_clickable(ignore_errors),
+        ])
+        .await
+    }
+
+    /// Wait until the element is at least partially within the current viewport.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub async fn in_viewport(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_in_viewport(ignore_errors)).await
+    }
+
+    /// Wait until the element has focus, e.g. after sending Tab or clicking it.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub async fn focused(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_focused(ignore_errors)).await
+    }
+
+    /// Wait until the element is clickable and is the element that would receive a click
+    /// at its center point, i.e. it is not covered by an overlay such as a modal backdrop.
+    /// NOTE: This requires JavaScript execution to be available.
+    pub async fn truly_clickable(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_truly_clickable(ignore_errors)).await
+    }
+
+    /// Wait until the element is clickable, then scroll it into view and check that it is
+    /// still displayed. Both checks are done together in the same poll iteration.
+    pub async fn clickable_and_visible(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_clickable_and_visible(ignore_errors)).await
+    }
+
     pub async fn has_class<N>(self, class_name: N) -> WebDriverResult<()>
     where
         N: Needle + Clone + Send + Sync + 'static,
@@ -149,6 +464,17 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_has_class(class_name, ignore_errors)).await
     }
 
+    /// Wait until any one of the element's class names matches the specified class name.
+    /// Each class name is matched separately, so a full match can be used to wait for a
+    /// single class.
+    pub async fn has_class_token<N>(self, class_name: N) -> WebDriverResult<()>
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_has_class_token(class_name, ignore_errors)).await
+    }
+
     pub async fn lacks_class<N>(self, class_name: N) -> WebDriverResult<()>
     where
         N: Needle + Clone + Send + Sync + 'static,
@@ -165,6 +491,19 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_has_text(text, ignore_errors)).await
     }
 
+    /// Wait until the element's text contains the specified substring.
+    /// This is shorthand for `has_text(StringMatch::new(substring).partial())`.
+    pub async fn text_contains(self, substring: &str) -> WebDriverResult<()> {
+        self.has_text(StringMatch::new(substring).partial()).await
+    }
+
+    /// Wait until the element has any non-whitespace text.
+    /// Use `has_text()` instead to wait for specific text.
+    pub async fn has_any_text(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_text_not_empty(ignore_errors)).await
+    }
+
     pub async fn lacks_text<N>(self, text: N) -> WebDriverResult<()>
     where
         N: Needle + Clone + Send + Sync + 'static,
@@ -173,6 +512,9 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_lacks_text(text, ignore_errors)).await
     }
 
+    /// Wait until the element's value matches the specified value.
+    /// This is the waiter equivalent of `ElementQuery::with_value()`.
+    /// Elements without a value attribute never match.
     pub async fn has_value<N>(self, value: N) -> WebDriverResult<()>
     where
         N: Needle + Clone + Send + Sync + 'static,
@@ -189,6 +531,15 @@ impl<'a> ElementWaiter<'a> {
         self.condition(conditions::element_lacks_value(value, ignore_errors)).await
     }
 
+    /// Wait until the element has the specified HTML `name` attribute.
+    pub async fn has_name<N>(self, name: N) -> WebDriverResult<()>
+    where
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_has_name(name, ignore_errors)).await
+    }
+
     pub async fn has_attribute<S, N>(self, attribute_name: S, value: N) -> WebDriverResult<()>
     where
         S: Into<String>,
@@ -199,6 +550,25 @@ impl<'a> ElementWaiter<'a> {
             .await
     }
 
+    /// Wait until the element has the specified attribute with a value exactly equal to any
+    /// one of the specified values, e.g. `data-status` being "success" or "error".
+    pub async fn attribute_equals_any<S>(
+        self,
+        attribute_name: S,
+        values: Vec<String>,
+    ) -> WebDriverResult<()>
+    where
+        S: Into<String>,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_has_attribute_one_of(
+            attribute_name,
+            values,
+            ignore_errors,
+        ))
+        .await
+    }
+
     pub async fn lacks_attribute<S, N>(self, attribute_name: S, value: N) -> WebDriverResult<()>
     where
         S: Into<String>,
@@ -209,6 +579,26 @@ impl<'a> ElementWaiter<'a> {
             .await
     }
 
+    /// Wait until the specified attribute no longer matches the specified (old) value.
+    /// A missing attribute is treated as a change.
+    pub async fn attribute_changes_from<S, N>(
+        self,
+        attribute_name: S,
+        old_value: N,
+    ) -> WebDriverResult<()>
+    where
+        S: Into<String>,
+        N: Needle + Clone + Send + Sync + 'static,
+    {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_attribute_changes_from(
+            attribute_name,
+            old_value,
+            ignore_errors,
+        ))
+        .await
+    }
+
     pub async fn has_attributes<S, N>(self, desired_attributes: &[(S, N)]) -> WebDriverResult<()>
     where
         S: Into<String> + Clone,
@@ -345,6 +735,151 @@ impl ElementWaitable for WebElement<'_> {
     }
 }
 
+/// Wait for a condition on several elements at once.
+///
+/// Every element is checked on each poll iteration, and the wait succeeds only once the
+/// condition holds for all of them. This avoids waiting for each element in turn, where
+/// the timeouts would add up.
+///
+/// The default ElementPoller is taken from the session config of the first element.
+///
+/// ## Example:
+/// ```ignore
+/// let elems = driver.find_elements(By::Css("li")).await?;
+/// wait_all(&elems).error("Items not displayed").displayed().await?;
+/// ```
+pub fn wait_all<'a>(elements: &'a [WebElement<'a>]) -> MultiElementWaiter<'a> {
+    let poller: ElementPoller = elements
+        .first()
+        .and_then(|x| x.session.config().get("ElementPoller"))
+        .unwrap_or(ElementPoller::NoWait);
+    MultiElementWaiter::new(elements, poller)
+}
+
+/// Wait for a condition on several elements at once, with the specified error message
+/// in the case of timeout. This is shorthand for `wait_all(elements).error(message)`.
+pub fn wait_elements<'a>(elements: &'a [WebElement<'a>], message: &str) -> MultiElementWaiter<'a> {
+    wait_all(elements).error(message)
+}
+
+/// Waiter for a condition on several elements. See `wait_all()`.
+#[derive(Debug, Clone)]
+pub struct MultiElementWaiter<'a> {
+    elements: &'a [WebElement<'a>],
+    poller: ElementPoller,
+    message: String,
+    ignore_errors: bool,
+}
+
+impl<'a> MultiElementWaiter<'a> {
+    fn new(elements: &'a [WebElement<'a>], poller: ElementPoller) -> Self {
+        Self {
+            elements,
+            poller,
+            message: String::new(),
+            ignore_errors: true,
+        }
+    }
+
+    /// Use the specified ElementPoller for this MultiElementWaiter.
+    /// This will not affect the default ElementPoller used for other waits.
+    pub fn with_poller(mut self, poller: ElementPoller) -> Self {
+        self.poller = poller;
+        self
+    }
+
+    /// Provide a human-readable error message to be returned in the case of timeout.
+    pub fn error(mut self, message: &str) -> Self {
+        self.message = message.to_string();
+        self
+    }
+
+    /// By default a waiter will ignore any errors that occur while polling for the desired
+    /// condition(s). However, this behaviour can be modified so that the waiter will return
+    /// early if an error is returned from thirtyfour.
+    pub fn ignore_errors(mut self, ignore: bool) -> Self {
+        self.ignore_errors = ignore;
+        self
+    }
+
+    /// Force this MultiElementWaiter to wait for the specified timeout, polling once
+    /// after each interval. This will override the poller for this
+    /// MultiElementWaiter only.
+    pub fn wait(self, timeout: Duration, interval: Duration) -> Self {
+        self.with_poller(ElementPoller::TimeoutWithInterval(timeout, interval))
+    }
+
+    async fn run_poller(&self, conditions: Vec<ElementPredicate>) -> WebDriverResult<bool> {
+        if let Some(e) = self.poller.validation_error() {
+            return Err(e);
+        }
+        let mut ticker = ElementPollerTicker::new(self.poller.clone());
+        ticker.wait_for_delay().await;
+        loop {
+            let attempt_start = Instant::now();
+            let mut conditions_met = true;
+            'elements: for element in self.elements {
+                for f in &conditions {
+                    if !f(element).await? {
+                        conditions_met = false;
+                        break 'elements;
+                    }
+                }
+            }
+
+            if conditions_met {
+                return Ok(true);
+            }
+
+            ticker.record_latency(attempt_start.elapsed());
+            if !ticker.tick().await {
+                return Ok(false);
+            }
+        }
+    }
+
+    pub async fn condition(self, f: ElementPredicate) -> WebDriverResult<()> {
+        self.conditions(vec![f]).await
+    }
+
+    pub async fn conditions(self, conditions: Vec<ElementPredicate>) -> WebDriverResult<()> {
+        match self.run_poller(conditions).await? {
+            true => Ok(()),
+            false => Err(WebDriverError::Timeout(self.message)),
+        }
+    }
+
+    pub async fn displayed(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_displayed(ignore_errors)).await
+    }
+
+    pub async fn not_displayed(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_not_displayed(ignore_errors)).await
+    }
+
+    /// Wait until every element is displayed. This is an alias for `displayed()`.
+    pub async fn all_displayed(self) -> WebDriverResult<()> {
+        self.displayed().await
+    }
+
+    /// Wait until none of the elements are displayed. This is an alias for `not_displayed()`.
+    pub async fn none_displayed(self) -> WebDriverResult<()> {
+        self.not_displayed().await
+    }
+
+    pub async fn enabled(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_enabled(ignore_errors)).await
+    }
+
+    pub async fn clickable(self) -> WebDriverResult<()> {
+        let ignore_errors = self.ignore_errors;
+        self.condition(conditions::element_is_clickable(ignore_errors)).await
+    }
+}
+
 #[cfg(test)]
 /// This function checks if the public async methods implement Send. It is not intended to be executed.
 async fn _test_is_send() -> WebDriverResult<()> {
@@ -361,9 +896,31 @@ async fn _test_is_send() -> WebDriverResult<()> {
 
     // ElementWaitCondition
     is_send_val(&elem.wait_until().stale());
+    is_send_val(&elem.wait_until().present());
+    is_send_val(&elem.wait_until().not_present());
     is_send_val(&elem.wait_until().displayed());
     is_send_val(&elem.wait_until().selected());
     is_send_val(&elem.wait_until().enabled());
+    is_send_val(&elem.wait_until().enabled_and_clickable());
+    is_send_val(&elem.wait_until().clickable_and_visible());
+    is_send_val(&elem.wait_until().in_viewport());
+    is_send_val(&elem.wait_until().text_stabilizes());
+    is_send_val(&elem.wait_until().rect_stabilizes());
+    is_send_val(&elem.wait_until().displayed_then(|e| Box::pin(e.text())));
+    is_send_val(&elem.wait_until().refetch_with(By::Id("id")).enabled());
+    is_send_val(&elem.wait_until().has_any_text());
+    is_send_val(&elem.wait_until().focused());
+    is_send_val(&elem.wait_until().truly_clickable());
+    is_send_val(&elem.wait_until().displayed_get());
+    is_send_val(&elem.wait_until().clickable_get());
+    is_send_val(&elem.wait_until().initial_delay(Duration::from_millis(100)).clickable());
+
+    // MultiElementWaiter
+    let elems = driver.find_elements(By::Css(r#"div"#)).await?;
+    is_send_val(&wait_all(&elems).displayed());
+    is_send_val(&wait_all(&elems).enabled());
+    is_send_val(&wait_elements(&elems, "message").all_displayed());
+    is_send_val(&wait_elements(&elems, "message").none_displayed());
     is_send_val(&elem.wait_until().condition(Box::new(|elem| {
         Box::pin(async move { elem.is_enabled().await.or(Ok(false)) })
     })));